@@ -3,11 +3,14 @@ extern crate tokio_gql;
 #[macro_use]
 extern crate serde_json;
 extern crate futures;
+extern crate introspection;
 extern crate standalone_server;
 
-use futures::prelude::*;
+use futures::future;
+use introspection::Introspected;
 use serde_json as json;
-use tokio_gql::response::{leaf, rest, Response};
+use tokio_gql::resolver::{Resolver, ResolverFuture};
+use tokio_gql::service::ResolverService;
 
 pub mod schema {
     #[derive(SomethingCompletelyDifferent)]
@@ -19,51 +22,53 @@ struct EchoResolver {
     archive: Vec<String>,
 }
 
-#[derive(PartialEq, Debug)]
-struct Error;
-
-impl tokio_gql::service::GqlService for EchoResolver {
+impl Resolver for EchoResolver {
     type Schema = schema::Operation;
-    type Error = Error;
-
-    fn resolve(
-        &self,
-        request: Self::Schema,
-        response: Response<Error>,
-    ) -> Box<Future<Item = json::Value, Error = Self::Error>> {
-        let query_response = response.on(request.query, |req, _res| match req {
-            schema::EchoQuery::PastEchoes => leaf(req, Ok(json!(self.archive))),
-        });
+    type Responder = schema::RootResponder;
 
-        let mutation_response =
-            tokio_gql::response::Response::new().on(request.mutation, |req, res| match req {
-                schema::EchoMutation::Echo { message } => {
-                    rest(Ok(json::Value::String(match message {
-                        Some(msg) => msg.to_string(),
-                        None => "this probably should reply with an empty string".to_string(),
-                    })))
-                }
-            });
+    fn resolve(&self, request: Self::Schema, _responder: Self::Responder) -> ResolverFuture {
+        let data: json::Map<String, json::Value> = match request {
+            schema::Operation::Query { selection } => selection
+                .into_iter()
+                .map(|field| match field {
+                    schema::EchoQuery::PastEchoes { respond } => {
+                        (respond.response_key().to_string(), json!(self.archive))
+                    }
+                    schema::EchoQuery::__Typename { respond } => {
+                        (respond.response_key().to_string(), json!("EchoQuery"))
+                    }
+                })
+                .collect(),
+            schema::Operation::Mutation { selection } => selection
+                .into_iter()
+                .map(|field| match field {
+                    schema::EchoMutation::Echo { respond, message } => (
+                        respond.response_key().to_string(),
+                        json!(message.unwrap_or_default()),
+                    ),
+                    schema::EchoMutation::__Typename { respond } => {
+                        (respond.response_key().to_string(), json!("EchoMutation"))
+                    }
+                })
+                .collect(),
+        };
 
-        Box::new(
-            query_response
-                .into_future()
-                .join(mutation_response)
-                .map(|(q, m)| {
-                    json!({
-                        "data": {
-                            "query": q,
-                            "mutation": m,
-                        }
-                    })
-                }),
-        )
+        Box::new(future::ok(json::Value::Object(data).into()))
     }
 }
 
 fn main() {
-    standalone_server::StandaloneServer::new(EchoResolver {
-        archive: Vec::new(),
-    }).start()
+    let service = ResolverService::new(
+        schema::THE_SCHEMA,
+        Introspected::new(
+            EchoResolver {
+                archive: Vec::new(),
+            },
+            &schema::INTROSPECTION_SCHEMA,
+        ),
+    ).expect("the schema is valid");
+
+    standalone_server::StandaloneServer::new(service)
+        .start()
         .unwrap()
 }
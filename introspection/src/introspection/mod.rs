@@ -1,3 +1,2 @@
 pub mod resolver;
-pub mod schema;
 pub mod traits;
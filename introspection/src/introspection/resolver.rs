@@ -1,30 +1,836 @@
-use futures::prelude::*;
-use introspection::schema;
+use futures::future;
 use serde_json as json;
-use tokio_gql::introspection::Schema;
-use tokio_gql::resolver::Resolver;
-use tokio_gql::response::Response;
+use tokio_gql::coercion::{is_included, CoerceQueryDocument, CoercionError, Fragment};
+use tokio_gql::errors::ResolverError;
+use tokio_gql::graphql_parser::query::*;
+use tokio_gql::introspection::{self as model, Schema, TypeKind, TypeRef};
+use tokio_gql::query_validation::{select_operation, ValidationContext};
+use tokio_gql::resolver::{Resolver, ResolverFuture};
+use tokio_gql::response::Resolved;
 
+type FieldResult = Result<json::Value, ResolverError>;
+
+/// The `__schema` and `__type` meta-fields selected on the query root.
+#[derive(Debug, PartialEq)]
+pub struct IntrospectionQuery {
+    fields: Vec<Field>,
+    context: ValidationContext,
+}
+
+impl CoerceQueryDocument for IntrospectionQuery {
+    fn coerce(
+        document: &Document,
+        context: &ValidationContext,
+    ) -> Result<Vec<IntrospectionQuery>, CoercionError> {
+        let mut fields = Vec::new();
+
+        // The meta-fields are only defined on the query root type.
+        if let OperationDefinition::Query(query) =
+            select_operation(document, context.operation_name())
+                .map_err(|err| CoercionError::new(err.to_string()))?
+        {
+            collect_meta_fields(&query.selection_set, context, &mut fields)?;
+        }
+
+        if fields.is_empty() {
+            Ok(Vec::new())
+        } else {
+            Ok(vec![IntrospectionQuery {
+                fields,
+                context: context.clone(),
+            }])
+        }
+    }
+}
+
+fn collect_meta_fields(
+    selection_set: &SelectionSet,
+    context: &ValidationContext,
+    fields: &mut Vec<Field>,
+) -> Result<(), CoercionError> {
+    for item in selection_set.items.iter() {
+        if !is_included(item, context)? {
+            continue;
+        }
+
+        match item {
+            Selection::Field(field) => {
+                if field.name == "__schema" || field.name == "__type" {
+                    fields.push(field.clone())
+                }
+            }
+            Selection::FragmentSpread(spread) => {
+                let fragment = Fragment::from_spread(spread, context)?;
+                collect_meta_fields(fragment.selection_set, context, fields)?;
+            }
+            Selection::InlineFragment(inline) => {
+                collect_meta_fields(&inline.selection_set, context, fields)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Answers introspection queries from the `INTROSPECTION_SCHEMA` constant generated by the derive.
 pub struct IntrospectionResolver {
-    schema_root: Schema,
+    schema: &'static Schema,
 }
 
 impl IntrospectionResolver {
-    pub fn new(schema_root: Schema) -> IntrospectionResolver {
-        IntrospectionResolver { schema_root }
+    pub fn new(schema: &'static Schema) -> IntrospectionResolver {
+        IntrospectionResolver { schema }
+    }
+
+    /// The response to the meta-fields of the query, as a JSON object.
+    pub fn resolve_query(&self, query: &IntrospectionQuery) -> FieldResult {
+        let context = &query.context;
+        let mut response = json::Map::new();
+
+        for field in query.fields.iter() {
+            let value = match field.name.as_str() {
+                "__schema" => self.resolve_schema(&field.selection_set, context)?,
+                "__type" => {
+                    let name = string_argument(field, "name", context).ok_or_else(|| {
+                        ResolverError::new("The name argument of __type is required")
+                    })?;
+                    self.resolve_named_type(Some(&name), &field.selection_set, context)?
+                }
+                other => return Err(unknown_field(other, "the query root")),
+            };
+
+            insert(&mut response, response_key(field), value);
+        }
+
+        Ok(json::Value::Object(response))
+    }
+
+    fn resolve_schema(
+        &self,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        let schema = self.schema;
+
+        resolve_object(selection_set, "__Schema", context, &|field: &Field| {
+            let selection_set = &field.selection_set;
+
+            match field.name.as_str() {
+                "types" => resolve_list(schema.types.iter(), |ty| {
+                    self.resolve_type(ty, selection_set, context)
+                }),
+                "queryType" => self.resolve_named_type(schema.query_type, selection_set, context),
+                "mutationType" => {
+                    self.resolve_named_type(schema.mutation_type, selection_set, context)
+                }
+                "subscriptionType" => {
+                    self.resolve_named_type(schema.subscription_type, selection_set, context)
+                }
+                "directives" => resolve_list(schema.directives.iter(), |directive| {
+                    self.resolve_directive(directive, selection_set, context)
+                }),
+                other => Err(unknown_field(other, "__Schema")),
+            }
+        })
+    }
+
+    /// Types that are not part of the introspection data resolve to `null`.
+    fn resolve_named_type(
+        &self,
+        name: Option<&str>,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        match name.and_then(|name| self.schema.type_by_name(name)) {
+            Some(ty) => self.resolve_type(ty, selection_set, context),
+            None => Ok(json::Value::Null),
+        }
+    }
+
+    fn resolve_type_ref(
+        &self,
+        type_ref: &TypeRef,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        let (kind, of_type) = match type_ref {
+            TypeRef::Named(name) => {
+                return self.resolve_named_type(Some(name), selection_set, context)
+            }
+            TypeRef::List(of_type) => (TypeKind::List, of_type),
+            TypeRef::NonNull(of_type) => (TypeKind::NonNull, of_type),
+        };
+
+        resolve_object(
+            selection_set,
+            "__Type",
+            context,
+            &|field: &Field| match field.name.as_str() {
+                "kind" => Ok(json!(kind)),
+                "ofType" => self.resolve_type_ref(of_type, &field.selection_set, context),
+                "name" | "description" | "fields" | "interfaces" | "possibleTypes"
+                | "enumValues" | "inputFields" => Ok(json::Value::Null),
+                other => Err(unknown_field(other, "__Type")),
+            },
+        )
+    }
+
+    fn resolve_type(
+        &self,
+        ty: &model::Type,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        resolve_object(selection_set, "__Type", context, &|field: &Field| {
+            match field.name.as_str() {
+                "kind" => Ok(json!(ty.kind)),
+                "name" => Ok(json!(ty.name)),
+                "description" => Ok(json!(ty.description)),
+                "fields" => match ty.kind {
+                    TypeKind::Object | TypeKind::Interface => {
+                        let include_deprecated = include_deprecated(field, context);
+                        let fields = ty.fields.iter().filter(|schema_field| {
+                            include_deprecated || !schema_field.is_deprecated
+                        });
+
+                        resolve_list(fields, |schema_field| {
+                            self.resolve_field(schema_field, &field.selection_set, context)
+                        })
+                    }
+                    _ => Ok(json::Value::Null),
+                },
+                "interfaces" => match ty.kind {
+                    TypeKind::Object => resolve_list(ty.interfaces.iter(), |name| {
+                        self.resolve_named_type(Some(name), &field.selection_set, context)
+                    }),
+                    _ => Ok(json::Value::Null),
+                },
+                "possibleTypes" => match ty.kind {
+                    TypeKind::Interface | TypeKind::Union => {
+                        resolve_list(ty.possible_types.iter(), |name| {
+                            self.resolve_named_type(Some(name), &field.selection_set, context)
+                        })
+                    }
+                    _ => Ok(json::Value::Null),
+                },
+                "enumValues" => match ty.kind {
+                    TypeKind::Enum => {
+                        let include_deprecated = include_deprecated(field, context);
+                        let values = ty
+                            .enum_values
+                            .iter()
+                            .filter(|value| include_deprecated || !value.is_deprecated);
+
+                        resolve_list(values, |value| {
+                            self.resolve_enum_value(value, &field.selection_set, context)
+                        })
+                    }
+                    _ => Ok(json::Value::Null),
+                },
+                "inputFields" => match ty.kind {
+                    TypeKind::InputObject => resolve_list(ty.input_fields.iter(), |input_field| {
+                        self.resolve_input_value(input_field, &field.selection_set, context)
+                    }),
+                    _ => Ok(json::Value::Null),
+                },
+                // Named types do not wrap another type.
+                "ofType" => Ok(json::Value::Null),
+                other => Err(unknown_field(other, "__Type")),
+            }
+        })
+    }
+
+    fn resolve_field(
+        &self,
+        schema_field: &model::Field,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        resolve_object(
+            selection_set,
+            "__Field",
+            context,
+            &|field: &Field| match field.name.as_str() {
+                "name" => Ok(json!(schema_field.name)),
+                "description" => Ok(json!(schema_field.description)),
+                "args" => resolve_list(schema_field.args.iter(), |arg| {
+                    self.resolve_input_value(arg, &field.selection_set, context)
+                }),
+                "type" => self.resolve_type_ref(&schema_field.type_, &field.selection_set, context),
+                "isDeprecated" => Ok(json!(schema_field.is_deprecated)),
+                "deprecationReason" => Ok(json!(schema_field.deprecation_reason)),
+                other => Err(unknown_field(other, "__Field")),
+            },
+        )
+    }
+
+    fn resolve_input_value(
+        &self,
+        input_value: &model::InputValue,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        resolve_object(
+            selection_set,
+            "__InputValue",
+            context,
+            &|field: &Field| match field.name.as_str() {
+                "name" => Ok(json!(input_value.name)),
+                "description" => Ok(json!(input_value.description)),
+                "type" => self.resolve_type_ref(&input_value.type_, &field.selection_set, context),
+                "defaultValue" => Ok(json!(input_value.default_value)),
+                other => Err(unknown_field(other, "__InputValue")),
+            },
+        )
+    }
+
+    fn resolve_enum_value(
+        &self,
+        enum_value: &model::EnumValue,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        resolve_object(
+            selection_set,
+            "__EnumValue",
+            context,
+            &|field: &Field| match field.name.as_str() {
+                "name" => Ok(json!(enum_value.name)),
+                "description" => Ok(json!(enum_value.description)),
+                "isDeprecated" => Ok(json!(enum_value.is_deprecated)),
+                "deprecationReason" => Ok(json!(enum_value.deprecation_reason)),
+                other => Err(unknown_field(other, "__EnumValue")),
+            },
+        )
+    }
+
+    fn resolve_directive(
+        &self,
+        directive: &model::Directive,
+        selection_set: &SelectionSet,
+        context: &ValidationContext,
+    ) -> FieldResult {
+        resolve_object(
+            selection_set,
+            "__Directive",
+            context,
+            &|field: &Field| match field.name.as_str() {
+                "name" => Ok(json!(directive.name)),
+                "description" => Ok(json!(directive.description)),
+                "locations" => Ok(json!(directive.locations)),
+                "args" => resolve_list(directive.args.iter(), |arg| {
+                    self.resolve_input_value(arg, &field.selection_set, context)
+                }),
+                other => Err(unknown_field(other, "__Directive")),
+            },
+        )
+    }
+}
+
+impl Resolver for IntrospectionResolver {
+    type Schema = IntrospectionQuery;
+    type Responder = ();
+
+    fn resolve(&self, request: IntrospectionQuery, _responder: ()) -> ResolverFuture {
+        Box::new(future::result(
+            self.resolve_query(&request)
+                .map(Resolved::from)
+                .map_err(|err| vec![err]),
+        ))
     }
 }
 
+/// An operation for an `Introspected` resolver: either an operation of the wrapped resolver, or the introspection meta-fields of the query.
 #[derive(Debug, PartialEq)]
-pub struct Error;
+pub enum IntrospectedOperation<Operation> {
+    Operation(Operation),
+    Introspection(IntrospectionQuery),
+}
 
-impl Resolver for IntrospectionResolver {
-    type Schema = schema::Operation;
+impl<Operation: CoerceQueryDocument> CoerceQueryDocument for IntrospectedOperation<Operation> {
+    fn coerce(
+        document: &Document,
+        context: &ValidationContext,
+    ) -> Result<Vec<IntrospectedOperation<Operation>>, CoercionError> {
+        let mut operations: Vec<_> = Operation::coerce(document, context)?
+            .into_iter()
+            .map(IntrospectedOperation::Operation)
+            .collect();
+        operations.extend(
+            IntrospectionQuery::coerce(document, context)?
+                .into_iter()
+                .map(IntrospectedOperation::Introspection),
+        );
+        Ok(operations)
+    }
+}
 
-    fn resolve(
-        &self,
-        request: Self::Schema,
-    ) -> Box<Future<Item = json::Value, Error = Vec<::tokio_gql::errors::ResolverError>>> {
-        unimplemented!();
+/// Wraps a resolver to answer the introspection meta-fields along with the fields it resolves. The responses are merged by `execute`.
+///
+/// Validation accepts `__schema` and `__type` for any resolver. Without this wrapper they are missing from the response: `__type` is null, and `__schema` is reported as a missing non-null field.
+pub struct Introspected<R> {
+    resolver: R,
+    introspection: IntrospectionResolver,
+}
+
+impl<R> Introspected<R> {
+    pub fn new(resolver: R, schema: &'static Schema) -> Introspected<R> {
+        Introspected {
+            resolver,
+            introspection: IntrospectionResolver::new(schema),
+        }
+    }
+}
+
+impl<R: Resolver> Resolver for Introspected<R> {
+    type Schema = IntrospectedOperation<R::Schema>;
+    type Responder = R::Responder;
+
+    fn resolve(&self, request: Self::Schema, responder: R::Responder) -> ResolverFuture {
+        match request {
+            IntrospectedOperation::Operation(operation) => {
+                self.resolver.resolve(operation, responder)
+            }
+            IntrospectedOperation::Introspection(query) => self.introspection.resolve(query, ()),
+        }
+    }
+}
+
+/// Resolves a selection set on the introspection type named `typename`. `resolve_field` is called for every selected field but `__typename`.
+fn resolve_object(
+    selection_set: &SelectionSet,
+    typename: &str,
+    context: &ValidationContext,
+    resolve_field: &Fn(&Field) -> FieldResult,
+) -> FieldResult {
+    let mut response = json::Map::new();
+    fill_object(
+        &mut response,
+        selection_set,
+        typename,
+        context,
+        resolve_field,
+    )?;
+    Ok(json::Value::Object(response))
+}
+
+fn fill_object(
+    response: &mut json::Map<String, json::Value>,
+    selection_set: &SelectionSet,
+    typename: &str,
+    context: &ValidationContext,
+    resolve_field: &Fn(&Field) -> FieldResult,
+) -> Result<(), ResolverError> {
+    for item in selection_set.items.iter() {
+        if !is_included(item, context).map_err(invalid_selection)? {
+            continue;
+        }
+
+        let fragment = match item {
+            Selection::Field(field) => {
+                let value = if field.name == "__typename" {
+                    json!(typename)
+                } else {
+                    resolve_field(field)?
+                };
+                insert(response, response_key(field), value);
+                continue;
+            }
+            Selection::FragmentSpread(spread) => {
+                Fragment::from_spread(spread, context).map_err(invalid_selection)?
+            }
+            Selection::InlineFragment(inline) => Fragment::from_inline(inline),
+        };
+
+        if fragment
+            .type_condition
+            .map(|condition| condition == typename)
+            .unwrap_or(true)
+        {
+            fill_object(
+                response,
+                fragment.selection_set,
+                typename,
+                context,
+                resolve_field,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_list<T>(
+    items: impl Iterator<Item = T>,
+    resolve_item: impl Fn(T) -> FieldResult,
+) -> FieldResult {
+    items
+        .map(resolve_item)
+        .collect::<Result<Vec<_>, _>>()
+        .map(json::Value::Array)
+}
+
+/// The same response key can be selected more than once, for example directly and through a fragment. The sub-selections are merged in that case.
+fn insert(response: &mut json::Map<String, json::Value>, key: &str, value: json::Value) {
+    match response.get_mut(key) {
+        Some(existing) => return merge(existing, value),
+        None => (),
+    }
+
+    response.insert(key.to_string(), value);
+}
+
+fn merge(existing: &mut json::Value, value: json::Value) {
+    match (existing, value) {
+        (json::Value::Object(existing), json::Value::Object(value)) => {
+            for (key, value) in value {
+                insert(existing, &key, value);
+            }
+        }
+        (json::Value::Array(existing), json::Value::Array(values)) => {
+            for (existing, value) in existing.iter_mut().zip(values) {
+                merge(existing, value);
+            }
+        }
+        (existing, value) => *existing = value,
+    }
+}
+
+fn response_key(field: &Field) -> &str {
+    field.alias.as_ref().unwrap_or(&field.name)
+}
+
+fn string_argument(field: &Field, name: &str, context: &ValidationContext) -> Option<String> {
+    field
+        .arguments
+        .iter()
+        .find(|(argument_name, _)| argument_name == name)
+        .and_then(|(_, value)| match value {
+            Value::String(value) => Some(value.clone()),
+            Value::Variable(variable) => context
+                .variables
+                .get(variable)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string()),
+            _ => None,
+        })
+}
+
+/// The `includeDeprecated` argument of `__Type.fields` and `__Type.enumValues`, `false` by default.
+fn include_deprecated(field: &Field, context: &ValidationContext) -> bool {
+    field
+        .arguments
+        .iter()
+        .find(|(argument_name, _)| argument_name == "includeDeprecated")
+        .and_then(|(_, value)| match value {
+            Value::Boolean(include) => Some(*include),
+            Value::Variable(variable) => context
+                .variables
+                .get(variable)
+                .and_then(|value| value.as_bool()),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+fn unknown_field(name: &str, typename: &str) -> ResolverError {
+    ResolverError::new(format!("Unknown field {} on {}", name, typename))
+}
+
+fn invalid_selection(err: CoercionError) -> ResolverError {
+    ResolverError::new(format!(
+        "Invalid selection in introspection query: {}",
+        err.reason()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::prelude::*;
+    use tokio_gql::graphql_parser;
+    use tokio_gql::introspection::{InputValue, Type};
+
+    const SCHEMA: Schema = Schema {
+        types: &[
+            Type {
+                kind: TypeKind::Object,
+                name: Some("Query"),
+                description: None,
+                fields: &[model::Field {
+                    name: "dog",
+                    description: Some("Finds a dog by name"),
+                    args: &[InputValue {
+                        name: "name",
+                        description: None,
+                        type_: TypeRef::Named("String"),
+                        default_value: None,
+                    }],
+                    type_: TypeRef::Named("Dog"),
+                    is_deprecated: false,
+                    deprecation_reason: None,
+                }],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[],
+            },
+            Type {
+                kind: TypeKind::Object,
+                name: Some("Dog"),
+                description: Some("A good boy"),
+                fields: &[
+                    model::Field {
+                        name: "name",
+                        description: None,
+                        args: &[],
+                        type_: TypeRef::NonNull(&TypeRef::Named("String")),
+                        is_deprecated: false,
+                        deprecation_reason: None,
+                    },
+                    model::Field {
+                        name: "breed",
+                        description: None,
+                        args: &[],
+                        type_: TypeRef::Named("String"),
+                        is_deprecated: true,
+                        deprecation_reason: Some("All dogs are good dogs"),
+                    },
+                ],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[],
+            },
+            Type {
+                kind: TypeKind::Scalar,
+                name: Some("String"),
+                description: None,
+                fields: &[],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[],
+            },
+        ],
+        query_type: Some("Query"),
+        mutation_type: None,
+        subscription_type: None,
+        directives: &[],
+    };
+
+    fn introspect(query: &str, variables: json::Value) -> json::Value {
+        let document = graphql_parser::parse_query(query).unwrap();
+        let mut context = ValidationContext::new(match variables {
+            json::Value::Object(variables) => variables,
+            _ => json::Map::new(),
+        });
+
+        for definition in document.definitions.iter() {
+            if let Definition::Fragment(fragment) = definition {
+                context.push_fragment_definition(fragment);
+            }
+        }
+
+        let mut queries = IntrospectionQuery::coerce(&document, &context).unwrap();
+        assert_eq!(queries.len(), 1);
+
+        IntrospectionResolver::new(&SCHEMA)
+            .resolve(queries.remove(0), ())
+            .wait()
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn schema_introspection() {
+        assert_eq!(
+            introspect(
+                r##"
+                query {
+                    __schema {
+                        queryType { name }
+                        mutationType { name }
+                        types { kind name }
+                        directives { name }
+                    }
+                }
+                "##,
+                json!(null)
+            ),
+            json!({
+                "__schema": {
+                    "queryType": { "name": "Query" },
+                    "mutationType": null,
+                    "types": [
+                        { "kind": "OBJECT", "name": "Query" },
+                        { "kind": "OBJECT", "name": "Dog" },
+                        { "kind": "SCALAR", "name": "String" },
+                    ],
+                    "directives": [],
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn type_introspection_with_fragments_and_aliases() {
+        assert_eq!(
+            introspect(
+                r##"
+                query Introspect($name: String!) {
+                    query: __type(name: "Query") {
+                        fields {
+                            name
+                            args { name type { name } defaultValue }
+                            type { ...TypeFields }
+                        }
+                    }
+                    dog: __type(name: $name) {
+                        __typename
+                        ...TypeFields
+                        ... on __Type { description }
+                        ... on __Field { args { name } }
+                    }
+                    cat: __type(name: "Cat") { name }
+                }
+
+                fragment TypeFields on __Type {
+                    kind
+                    name
+                    fields { name isDeprecated }
+                }
+                "##,
+                json!({ "name": "Dog" })
+            ),
+            json!({
+                "query": {
+                    "fields": [{
+                        "name": "dog",
+                        "args": [{ "name": "name", "type": { "name": "String" }, "defaultValue": null }],
+                        "type": {
+                            "kind": "OBJECT",
+                            "name": "Dog",
+                            "fields": [{ "name": "name", "isDeprecated": false }],
+                        },
+                    }],
+                },
+                "dog": {
+                    "__typename": "__Type",
+                    "kind": "OBJECT",
+                    "name": "Dog",
+                    "description": "A good boy",
+                    "fields": [{ "name": "name", "isDeprecated": false }],
+                },
+                "cat": null,
+            })
+        );
+    }
+
+    #[test]
+    fn wrapped_types() {
+        assert_eq!(
+            introspect(
+                r##"
+                query {
+                    __type(name: "Dog") {
+                        fields {
+                            type {
+                                kind
+                                name
+                                ofType { kind name ofType { name } }
+                            }
+                        }
+                    }
+                }
+                "##,
+                json!(null)
+            ),
+            json!({
+                "__type": {
+                    "fields": [{
+                        "type": {
+                            "kind": "NON_NULL",
+                            "name": null,
+                            "ofType": { "kind": "SCALAR", "name": "String", "ofType": null },
+                        }
+                    }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn deprecated_fields() {
+        assert_eq!(
+            introspect(
+                r##"
+                query Introspect($includeDeprecated: Boolean) {
+                    __type(name: "Dog") {
+                        fields { name }
+                        all: fields(includeDeprecated: true) {
+                            name
+                            isDeprecated
+                            deprecationReason
+                        }
+                        variable: fields(includeDeprecated: $includeDeprecated) { name }
+                    }
+                }
+                "##,
+                json!({ "includeDeprecated": true })
+            ),
+            json!({
+                "__type": {
+                    "fields": [{ "name": "name" }],
+                    "all": [
+                        { "name": "name", "isDeprecated": false, "deprecationReason": null },
+                        {
+                            "name": "breed",
+                            "isDeprecated": true,
+                            "deprecationReason": "All dogs are good dogs",
+                        },
+                    ],
+                    "variable": [{ "name": "name" }, { "name": "breed" }],
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn merged_selections() {
+        assert_eq!(
+            introspect(
+                r##"
+                query {
+                    __schema {
+                        types { name }
+                        ...on __Schema { types { kind } }
+                    }
+                }
+                "##,
+                json!(null)
+            ),
+            json!({
+                "__schema": {
+                    "types": [
+                        { "kind": "OBJECT", "name": "Query" },
+                        { "kind": "OBJECT", "name": "Dog" },
+                        { "kind": "SCALAR", "name": "String" },
+                    ],
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn queries_without_meta_fields() {
+        let document =
+            graphql_parser::parse_query("query { dog(name: \"Rex\") { name } }").unwrap();
+        let context = ValidationContext::new(json::Map::new());
+
+        assert_eq!(
+            IntrospectionQuery::coerce(&document, &context),
+            Ok(Vec::new())
+        );
     }
 }
@@ -1,3 +0,0 @@
-#[derive(SomethingCompletelyDifferent)]
-#[SomethingCompletelyDifferent(path = "src/introspection/schema.graphql")]
-struct IntrospectionSchema;
@@ -1,10 +1,18 @@
-#[macro_use]
-extern crate tokio_gql;
+//! Answers the `__schema` and `__type` introspection queries from the `INTROSPECTION_SCHEMA` constant generated by the derive.
+//!
+//! Wrap your resolver in `Introspected` to serve introspection queries alongside your own fields:
+//!
+//! ```ignore
+//! let resolver = Introspected::new(MyResolver, &schema::INTROSPECTION_SCHEMA);
+//! let service = ResolverService::new(schema::THE_SCHEMA, resolver)?;
+//! ```
+
 extern crate futures;
 #[macro_use]
-extern crate serde_derive;
 extern crate serde_json;
+extern crate tokio_gql;
 
 mod introspection;
 
-pub use introspection::resolver::IntrospectionResolver;
+pub use introspection::resolver::{Introspected, IntrospectedOperation, IntrospectionQuery,
+                                  IntrospectionResolver};
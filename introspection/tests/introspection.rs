@@ -0,0 +1,416 @@
+extern crate futures;
+extern crate introspection;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+#[macro_use]
+extern crate tokio_gql;
+
+use futures::prelude::*;
+use introspection::Introspected;
+use serde_json as json;
+use tokio_gql::execution::Request;
+use tokio_gql::resolver::{Resolver, ResolverFuture};
+use tokio_gql::service::{GqlService, ResolverService};
+
+mod schema {
+    #[allow(dead_code)]
+    #[derive(SomethingCompletelyDifferent)]
+    #[SomethingCompletelyDifferent(path = "tests/greetings.graphql")]
+    struct GreetingsSchema;
+}
+
+mod star_wars {
+    #[allow(dead_code)]
+    #[derive(SomethingCompletelyDifferent)]
+    #[SomethingCompletelyDifferent(path = "../tests/star_wars_schema.graphql")]
+    struct StarWarsSchema;
+}
+
+/// The query used by GraphiQL and most tools to fetch the whole schema.
+const INTROSPECTION_QUERY: &str = r##"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      ...FullType
+    }
+    directives {
+      name
+      description
+      locations
+      args {
+        ...InputValue
+      }
+    }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args {
+      ...InputValue
+    }
+    type {
+      ...TypeRef
+    }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields {
+    ...InputValue
+  }
+  interfaces {
+    ...TypeRef
+  }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes {
+    ...TypeRef
+  }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+        }
+      }
+    }
+  }
+}
+"##;
+
+/// Only answers introspection queries.
+struct StarWarsResolver;
+
+impl Resolver for StarWarsResolver {
+    type Schema = star_wars::Operation;
+    type Responder = star_wars::RootResponder;
+
+    fn resolve(
+        &self,
+        _request: star_wars::Operation,
+        _responder: Self::Responder,
+    ) -> ResolverFuture {
+        Box::new(futures::future::ok(json!({}).into()))
+    }
+}
+
+struct GreetingResolver;
+
+impl Resolver for GreetingResolver {
+    type Schema = schema::Operation;
+    type Responder = schema::RootResponder;
+
+    fn resolve(&self, request: schema::Operation, _responder: Self::Responder) -> ResolverFuture {
+        let schema::Operation::Query { selection } = request;
+        let data: json::Map<String, json::Value> = selection
+            .into_iter()
+            .map(|field| match field {
+                schema::Query::Greeting { respond, .. } => (
+                    respond.response_key().to_string(),
+                    json!({ "text": "Hello" }),
+                ),
+                schema::Query::__Typename { respond } => {
+                    (respond.response_key().to_string(), json!("Query"))
+                }
+            })
+            .collect();
+
+        Box::new(futures::future::ok(json::Value::Object(data).into()))
+    }
+}
+
+fn execute(query: &str) -> json::Value {
+    let service = ResolverService::new(
+        schema::THE_SCHEMA,
+        Introspected::new(GreetingResolver, &schema::INTROSPECTION_SCHEMA),
+    ).unwrap();
+    let result = service.handle_request(Request::new(query)).wait().unwrap();
+
+    json::to_value(&result).unwrap()
+}
+
+#[test]
+fn introspection_is_merged_with_the_resolver_response() {
+    assert_eq!(
+        execute(
+            r##"
+            query {
+                greeting(name: "Tom") { text }
+                __type(name: "Greeting") {
+                    kind
+                    name
+                    fields { name type { kind name ofType { kind name } } }
+                }
+            }
+            "##
+        ),
+        json!({
+            "data": {
+                "greeting": { "text": "Hello" },
+                "__type": {
+                    "kind": "OBJECT",
+                    "name": "Greeting",
+                    "fields": [
+                        {
+                            "name": "text",
+                            "type": {
+                                "kind": "NON_NULL",
+                                "name": null,
+                                "ofType": { "kind": "SCALAR", "name": "String" },
+                            },
+                        },
+                        {
+                            "name": "language",
+                            "type": { "kind": "SCALAR", "name": "String", "ofType": null },
+                        },
+                    ],
+                },
+            }
+        })
+    );
+}
+
+#[test]
+fn schema_introspection() {
+    let response = execute(
+        r##"
+        query IntrospectionQuery {
+            __schema {
+                queryType { name }
+                types { name }
+            }
+        }
+        "##,
+    );
+
+    assert_eq!(
+        response["data"]["__schema"]["queryType"],
+        json!({ "name": "Query" })
+    );
+
+    let type_names: Vec<&str> = response["data"]["__schema"]["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|ty| ty["name"].as_str())
+        .collect();
+
+    assert_eq!(
+        type_names,
+        vec!["Greeting", "Query", "Tone", "Boolean", "Float", "ID", "Int", "String"]
+    );
+}
+
+#[test]
+fn deprecated_fields_and_enum_values() {
+    assert_eq!(
+        execute(
+            r##"
+            query {
+                greeting: __type(name: "Greeting") {
+                    fields { name }
+                    allFields: fields(includeDeprecated: true) {
+                        name
+                        isDeprecated
+                        deprecationReason
+                    }
+                }
+                tone: __type(name: "Tone") {
+                    enumValues { name }
+                    allValues: enumValues(includeDeprecated: true) {
+                        name
+                        isDeprecated
+                        deprecationReason
+                    }
+                }
+            }
+            "##
+        ),
+        json!({
+            "data": {
+                "greeting": {
+                    "fields": [{ "name": "text" }, { "name": "language" }],
+                    "allFields": [
+                        { "name": "text", "isDeprecated": false, "deprecationReason": null },
+                        { "name": "language", "isDeprecated": false, "deprecationReason": null },
+                        {
+                            "name": "tone",
+                            "isDeprecated": true,
+                            "deprecationReason": "No longer supported",
+                        },
+                    ],
+                },
+                "tone": {
+                    "enumValues": [{ "name": "FRIENDLY" }],
+                    "allValues": [
+                        { "name": "FRIENDLY", "isDeprecated": false, "deprecationReason": null },
+                        {
+                            "name": "FORMAL",
+                            "isDeprecated": true,
+                            "deprecationReason": "Nobody is formal anymore",
+                        },
+                    ],
+                },
+            }
+        })
+    );
+}
+
+#[test]
+fn invalid_introspection_queries_are_rejected() {
+    assert_eq!(
+        execute("query { __schema { rootValue } }"),
+        json!({
+            "errors": [{
+                "message": "Cannot query field rootValue on type __Schema",
+                "locations": [{ "line": 1, "column": 20 }],
+            }]
+        })
+    );
+}
+
+fn find_type<'a>(types: &'a json::Value, name: &str) -> &'a json::Value {
+    types
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ty| ty["name"] == json!(name))
+        .expect("the type is in the introspection response")
+}
+
+fn names(values: &json::Value) -> Vec<&str> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value["name"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn full_introspection_query() {
+    let service = ResolverService::new(
+        star_wars::THE_SCHEMA,
+        Introspected::new(StarWarsResolver, &star_wars::INTROSPECTION_SCHEMA),
+    ).unwrap();
+    let response = json::to_value(
+        &service
+            .handle_request(Request::new(INTROSPECTION_QUERY))
+            .wait()
+            .unwrap(),
+    ).unwrap();
+
+    assert_eq!(response.get("errors"), None);
+
+    let schema = &response["data"]["__schema"];
+    let types = &schema["types"];
+
+    assert_eq!(schema["queryType"], json!({ "name": "Query" }));
+    assert_eq!(schema["mutationType"], json!({ "name": "Mutation" }));
+    assert_eq!(schema["subscriptionType"], json!({ "name": "Subscription" }));
+    assert_eq!(
+        names(&schema["directives"]),
+        vec!["skip", "include", "deprecated"]
+    );
+    assert_eq!(
+        schema["directives"][0]["locations"],
+        json!(["FIELD", "FRAGMENT_SPREAD", "INLINE_FRAGMENT"])
+    );
+    assert_eq!(
+        schema["directives"][2]["args"][0]["defaultValue"],
+        json!("\"No longer supported\"")
+    );
+
+    let character = find_type(types, "Character");
+    assert_eq!(character["kind"], json!("INTERFACE"));
+    assert_eq!(names(&character["possibleTypes"]), vec!["Human", "Droid"]);
+    assert_eq!(character["interfaces"], json!(null));
+
+    let human = find_type(types, "Human");
+    assert_eq!(
+        human["interfaces"],
+        json!([{ "kind": "INTERFACE", "name": "Character", "ofType": null }])
+    );
+    assert_eq!(human["enumValues"], json!(null));
+
+    let search_result = find_type(types, "SearchResult");
+    assert_eq!(search_result["kind"], json!("UNION"));
+    assert_eq!(search_result["fields"], json!(null));
+    assert_eq!(
+        names(&search_result["possibleTypes"]),
+        vec!["Human", "Droid", "Starship"]
+    );
+
+    let episode = find_type(types, "Episode");
+    assert_eq!(names(&episode["enumValues"]), vec!["NEWHOPE", "EMPIRE", "JEDI"]);
+    assert_eq!(episode["enumValues"][0]["isDeprecated"], json!(false));
+
+    let review_input = find_type(types, "ReviewInput");
+    assert_eq!(review_input["kind"], json!("INPUT_OBJECT"));
+    assert_eq!(review_input["fields"], json!(null));
+    assert_eq!(
+        names(&review_input["inputFields"]),
+        vec!["stars", "commentary", "favorite_color"]
+    );
+
+    let starship = find_type(types, "Starship");
+    let length = &starship["fields"][2];
+    assert_eq!(length["args"][0]["defaultValue"], json!("METER"));
+    let coordinates = &starship["fields"][3];
+    assert_eq!(
+        coordinates["type"],
+        json!({
+            "kind": "LIST",
+            "name": null,
+            "ofType": {
+                "kind": "NON_NULL",
+                "name": null,
+                "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                        "kind": "NON_NULL",
+                        "name": null,
+                        "ofType": { "kind": "SCALAR", "name": "Float" },
+                    },
+                },
+            },
+        })
+    );
+
+    assert_eq!(find_type(types, "ID")["kind"], json!("SCALAR"));
+}
@@ -54,76 +54,95 @@ impl ImplCoerce for ArgumentsContext {
             );
 
             let arguments_matchers = field.arguments.iter().map(|arg| {
-                let argument_type = shared::value_variant_for_type(&arg.value_type, &context);
+                let argument_pattern = shared::value_pattern_for_type(&arg.value_type, &context);
                 let term = Term::new(&arg.name.to_mixed_case(), Span::call_site());
                 let literal = Literal::string(&arg.name);
 
-                let coercion_target = resolve_coercion_target(&arg.value_type);
+                let coercion_target = resolve_coercion_target(&arg.value_type, context);
                 let coercion_target_type_name = &coercion_target.type_name;
+                let expected_type = arg.value_type.to_string();
+                let invalid_argument = quote! {
+                    ::tokio_gql::coercion::CoercionError::invalid_argument(field, #literal, #expected_type, context)
+                };
+                let inner_type_name = shared::extract_inner_name(&arg.value_type);
+                let variable_value = if context.is_custom_scalar(inner_type_name) {
+                    let value_type = resolve_coercion_target_inner(&arg.value_type, context, false).type_name;
+                    quote!(<#value_type as ::tokio_gql::coercion::CoerceScalar>::coerce_json(v).map_err(|err| err.in_argument(field, #literal)))
+                } else if context.is_scalar(inner_type_name) || context.is_enum(inner_type_name) {
+                    quote!(::serde_json::from_value(v.clone()).map_err(|_| #invalid_argument))
+                } else {
+                    // Input objects report which of their fields could not be deserialized, and why.
+                    quote!(::serde_json::from_value(v.clone()).map_err(|err| ::tokio_gql::coercion::CoercionError::new(err.to_string()).in_argument(field, #literal)))
+                };
 
                 if !coercion_target.optional {
                     quote! {
-                        let #term = field
-                            .arguments
-                            .iter()
-                            .find(|(name, _)| name == #literal)
-                            .and_then(|(_, value)| {
-                                match value {
-                                    #argument_type(_) => {
-                                        <#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(value).ok()
-                                    }
-                                    ::tokio_gql::graphql_parser::query::Value::Variable(name) => context.variables.get(name).and_then(|v| ::serde_json::from_value(v.clone()).ok()),
-                                    _ => None,
-                                }
-                            }).ok_or(::tokio_gql::coercion::CoercionError)?;
+                        let #term = match field.arguments.iter().find(|(name, _)| name == #literal) {
+                            Some((_, ::tokio_gql::graphql_parser::query::Value::Variable(name))) => match context.variables.get(name) {
+                                Some(v) => Some(#variable_value?),
+                                None => None,
+                            },
+                            Some((_, value)) => match value {
+                                #argument_pattern => Some(
+                                    <#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(value)
+                                        .map_err(|err| err.in_argument(field, #literal))?
+                                ),
+                                _ => None,
+                            },
+                            None => None,
+                        }.ok_or_else(|| #invalid_argument)?;
                     }
                 } else {
-                    if let Some(ref default) = arg.default_value {
-                        let default_literal = shared::query_value_to_tokens(default);
-                        quote! {
-                            let #term = field
-                                .arguments
-                                .iter()
-                                .find(|(name, _)| name == #literal)
-                                .map(|(_, value)| {
-                                    match value {
-                                        #argument_type(_) => {
-                                            <#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(value).expect("coercion on optional arguments cannot fail")
-                                        },
-                                        ::tokio_gql::graphql_parser::query::Value::Variable(name) => context.variables.get(name).and_then(|v| ::serde_json::from_value(v.clone()).ok()),
-                                        _ => None,
-                                    }
-                                }).or(<#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(&#default_literal).ok())
-                                .ok_or(::tokio_gql::coercion::CoercionError)?;
-                        }
-                    } else {
-                        quote! {
-                            let #term = field
-                                .arguments
-                                .iter()
-                                .find(|(name, _)| name == #literal)
-                                .map(|(_, value)| {
-                                    match value {
-                                        #argument_type(_) => {
-                                            <#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(value).expect("coercion on optional arguments cannot fail")
-                                        },
-                                        ::tokio_gql::graphql_parser::query::Value::Variable(name) => context.variables.get(name).and_then(|v| ::serde_json::from_value(v.clone()).ok()),
-                                        _ => None,
-                                    }
-                                }).ok_or(::tokio_gql::coercion::CoercionError)?;
+                    // Missing optional arguments take their default value, if any.
+                    let missing = match arg.default_value {
+                        Some(ref default) => {
+                            let default_literal = shared::query_value_to_tokens(default);
+                            quote!(<#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(&#default_literal).ok())
                         }
+                        None => quote!(Some(None)),
+                    };
+
+                    // Only null and absent values are `None`, other values have to be of the argument type.
+                    quote! {
+                        let #term = match field.arguments.iter().find(|(name, _)| name == #literal) {
+                            Some((_, ::tokio_gql::graphql_parser::query::Value::Variable(name))) => match context.variables.get(name) {
+                                None | Some(::serde_json::Value::Null) => Some(None),
+                                Some(v) => Some(Some(#variable_value?)),
+                            },
+                            Some((_, value)) => Some(
+                                <#coercion_target_type_name as ::tokio_gql::coercion::CoerceScalar>::coerce(value)
+                                    .map_err(|err| err.in_argument(field, #literal))?
+                            ),
+                            None => #missing,
+                        }.ok_or_else(|| #invalid_argument)?;
                     }
                 }
             });
 
+            // Absent optional arguments are `None`, so unknown arguments have to be reported separately.
+            let argument_names = field.arguments.iter().map(|arg| arg.name.as_str());
+
             quote! {
                 if field.name == #variant_name_literal {
+                    let known_arguments: &[&str] = &[#(#argument_names),*];
+                    if let Some((name, _)) = field.arguments.iter().find(|(name, _)| !known_arguments.contains(&name.as_str())) {
+                        return Err(::tokio_gql::coercion::CoercionError::new("unknown argument").in_argument(field, name));
+                    }
+
                     #(#arguments_matchers)*
                     result.push(#variant_constructor)
                 }
             }
         });
-        quote!(#(#matchers)*)
+        let typename_constructor = shared::typename_constructor(&self.object_name);
+
+        quote! {
+            #(#matchers)*
+
+            if field.name == "__typename" {
+                result.push(#typename_constructor)
+            }
+        }
     }
 }
 
@@ -139,7 +158,11 @@ fn field_variant_constructor(
         .map(|arg| Term::new(&arg.name.to_mixed_case(), Span::call_site()))
         .collect();
     let argument_idents_clone = argument_idents.clone();
-    let responder = quote! { respond: ::std::default::Default::default() };
+    let responder_type = Term::new(
+        &format!("{}{}Responder", field_name.as_str(), variant_name.as_str()),
+        Span::call_site(),
+    );
+    let responder = quote! { respond: #responder_type { alias: field.alias.clone() } };
     if argument_idents.is_empty()
         && (context.is_scalar(field_type_name) || context.is_enum(field_type_name))
     {
@@ -148,10 +171,10 @@ fn field_variant_constructor(
         && !(context.is_scalar(field_type_name) || context.is_enum(field_type_name))
     {
         let field_type = Term::new(field_type_name, Span::call_site());
-        quote!(#field_name::#variant_name { #responder, selection: <#field_type as ::tokio_gql::coercion::CoerceSelection>::coerce(&field.selection_set, context).unwrap(), #(#argument_idents_clone),* })
+        quote!(#field_name::#variant_name { #responder, selection: <#field_type as ::tokio_gql::coercion::CoerceSelection>::coerce(&field.selection_set, context).map_err(|err| err.in_field(field))?, #(#argument_idents_clone),* })
     } else if argument_idents.is_empty() {
         let field_type = Term::new(field_type_name, Span::call_site());
-        quote!(#field_name::#variant_name { #responder, selection: <#field_type as ::tokio_gql::coercion::CoerceSelection>::coerce(&field.selection_set, context).unwrap() })
+        quote!(#field_name::#variant_name { #responder, selection: <#field_type as ::tokio_gql::coercion::CoerceSelection>::coerce(&field.selection_set, context).map_err(|err| err.in_field(field))? })
     } else {
         quote!(#field_name::#variant_name { #responder, #(#argument_idents_clone),* })
     }
@@ -167,19 +190,23 @@ struct CoercionTarget {
 }
 
 /// Given a schema argument, resolve what it should coerce to
-fn resolve_coercion_target(arg: &graphql_parser::query::Type) -> CoercionTarget {
-    resolve_coercion_target_inner(arg, true)
+fn resolve_coercion_target(
+    arg: &graphql_parser::query::Type,
+    context: &DeriveContext,
+) -> CoercionTarget {
+    resolve_coercion_target_inner(arg, context, true)
 }
 
 fn resolve_coercion_target_inner(
     arg: &graphql_parser::query::Type,
+    context: &DeriveContext,
     optional: bool,
 ) -> CoercionTarget {
     use graphql_parser::query::Type;
 
     match arg {
         Type::ListType(inner) => {
-            let inner_target = resolve_coercion_target_inner(inner, true).type_name;
+            let inner_target = resolve_coercion_target_inner(inner, context, true).type_name;
             CoercionTarget {
                 optional: true,
                 type_name: if optional {
@@ -190,14 +217,15 @@ fn resolve_coercion_target_inner(
             }
         }
         Type::NonNullType(inner) => {
-            let CoercionTarget { type_name, .. } = resolve_coercion_target_inner(inner, false);
+            let CoercionTarget { type_name, .. } =
+                resolve_coercion_target_inner(inner, context, false);
             CoercionTarget {
                 optional: false,
                 type_name,
             }
         }
         Type::NamedType(inner) => {
-            let term_inner = Term::new(shared::correspondant_type(inner), Span::call_site());
+            let term_inner = shared::correspondant_type(inner, context);
             CoercionTarget {
                 // This is always ignored
                 optional,
@@ -222,7 +250,7 @@ mod tests {
         macro_rules! test {
             ($input:expr => $expected:expr) => {{
                 let expectation = $expected;
-                let actual = resolve_coercion_target(&$input);
+                let actual = resolve_coercion_target(&$input, &DeriveContext::new());
                 assert_eq!(expectation, actual)
             }};
         }
@@ -8,6 +8,7 @@ use quote;
 impl ImplCoerce for EnumType {
     fn impl_coerce(&self, _context: &DeriveContext) -> quote::Tokens {
         let name_term = Term::new(&self.name, Span::call_site());
+        let name_literal = self.name.as_str();
         let matchers = self.values.iter().map(|value| {
             let value_term = Term::new(&value.name.to_camel_case(), Span::call_site());
             let value_lit = &value.name;
@@ -19,6 +20,7 @@ impl ImplCoerce for EnumType {
         });
 
         quote! {
+            #[allow(deprecated)]
             impl ::tokio_gql::coercion::CoerceScalar for #name_term {
                 fn coerce(
                     query: &::tokio_gql::graphql_parser::query::Value,
@@ -27,7 +29,7 @@ impl ImplCoerce for EnumType {
                         #(#matchers)*
                     }
 
-                    Err(::tokio_gql::coercion::CoercionError)
+                    Err(::tokio_gql::coercion::CoercionError::invalid_value(#name_literal, query))
                 }
             }
         }
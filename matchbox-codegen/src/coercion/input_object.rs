@@ -8,6 +8,7 @@ use shared;
 impl ImplCoerce for InputObjectType {
     fn impl_coerce(&self, context: &DeriveContext) -> quote::Tokens {
         let name = Term::new(&self.name, Span::call_site());
+        let name_literal = self.name.as_str();
         let field_name_literals: Vec<String> =
             self.fields.iter().map(|i| i.name.to_string()).collect();
 
@@ -18,38 +19,47 @@ impl ImplCoerce for InputObjectType {
 
         let field_types: Vec<_> = self.fields
             .iter()
-            .map(|i| shared::gql_type_to_json_type(&i.value_type))
+            .map(|i| shared::gql_type_to_json_type(&i.value_type, context))
             .collect();
 
         let extractors: Vec<quote::Tokens> = self.fields
             .iter()
             .enumerate()
             .map(|(idx, f)| {
-                let value_variant = shared::value_variant_for_type(&f.value_type, context);
+                let value_pattern = shared::value_pattern_for_type(&f.value_type, context);
                 let field_type = &field_types[idx];
                 let field_name_term = &field_name_terms[idx];
                 let field_name_literal = &field_name_literals[idx];
+                let expected_type = f.value_type.to_string();
                 if shared::type_is_optional(&f.value_type) {
+                    // Only null and absent values are `None`, other values have to be of the field type.
                     quote!{
-                        let #field_name_term = obj.get(#field_name_literal)
-                            .and_then(|value| {
-                                if let #value_variant(_) = value {
-                                    <#field_type as ::tokio_gql::coercion::CoerceScalar>::coerce(value).expect("should be fine")
-                                } else {
-                                    None
-                                }
-                            });
+                        let #field_name_term = match obj.get(#field_name_literal) {
+                            Some(value) => <#field_type as ::tokio_gql::coercion::CoerceScalar>::coerce(value)
+                                .map_err(|_| ::tokio_gql::coercion::CoercionError::invalid_input_field(
+                                    #name_literal,
+                                    #field_name_literal,
+                                    #expected_type,
+                                    Some(value),
+                                ))?,
+                            None => None,
+                        };
                     }
                 } else {
                     quote!{
                         let #field_name_term = obj.get(#field_name_literal)
                             .and_then(|value| {
-                                if let #value_variant(_) = value {
+                                if let #value_pattern = value {
                                     <#field_type as ::tokio_gql::coercion::CoerceScalar>::coerce(value).ok()
                                 } else {
                                     None
                                 }
-                            }).ok_or(::tokio_gql::coercion::CoercionError)?;
+                            }).ok_or_else(|| ::tokio_gql::coercion::CoercionError::invalid_input_field(
+                                #name_literal,
+                                #field_name_literal,
+                                #expected_type,
+                                obj.get(#field_name_literal),
+                            ))?;
                     }
                 }
             })
@@ -73,7 +83,7 @@ impl ImplCoerce for InputObjectType {
 
                         Ok(#object_constructor)
                     } else {
-                        Err(::tokio_gql::coercion::CoercionError)
+                        Err(::tokio_gql::coercion::CoercionError::invalid_value(#name_literal, query))
                     }
                 }
             }
@@ -1,4 +1,4 @@
-use super::unions::spread_matchers_for_types;
+use super::unions::fragment_matchers_for_types;
 use coercion::arguments::ArgumentsContext;
 use coercion::traits::*;
 use context::DeriveContext;
@@ -15,24 +15,14 @@ impl ImplCoerce for InterfaceType {
             object_name: name_term.clone(),
         }.impl_coerce(&context);
 
-        let implementor_names: Vec<_> = context
-            .object_types
-            .iter()
-            .filter_map(|obj| {
-                if obj.implements_interfaces
-                    .iter()
-                    .any(|iface| iface.as_str() == self.name.as_str())
-                {
-                    Some(&obj.name)
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let spread_matchers =
-            spread_matchers_for_types(name_term.clone(), implementor_names.into_iter());
+        let fragment_matchers = fragment_matchers_for_types(
+            name_term.clone(),
+            context.possible_types(&self.name).into_iter(),
+            context,
+        );
 
         quote! {
+            #[allow(deprecated)]
             impl ::tokio_gql::coercion::CoerceSelection for #name_term {
                 fn coerce(
                     query: &::tokio_gql::graphql_parser::query::SelectionSet,
@@ -40,14 +30,22 @@ impl ImplCoerce for InterfaceType {
                 ) -> Result<Vec<#name_term>, ::tokio_gql::coercion::CoercionError> {
                     let mut results = Vec::new();
                     for selection in query.items.iter() {
+                        if !::tokio_gql::coercion::is_included(selection, context)? {
+                            continue;
+                        }
+
                         match selection {
                             ::tokio_gql::graphql_parser::query::Selection::Field(field) => {
                                 let mut result = &mut results;
                                 #field_matchers
                             }
-                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(_) => unimplemented!("fragment spread on interface"),
-                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(fragment) => {
-                                #(#spread_matchers)*
+                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(spread) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_spread(spread, context)?;
+                                #fragment_matchers
+                            }
+                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(inline) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_inline(inline);
+                                #fragment_matchers
                             }
                         }
                     }
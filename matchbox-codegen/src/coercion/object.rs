@@ -13,7 +13,27 @@ impl ImplCoerce for ObjectType {
             object_name: Term::new(&self.name, Span::call_site()),
         }.impl_coerce(context);
 
+        // A fragment applies to the object if its type condition is the object itself, one of its interfaces or a union it is part of.
+        let name_literal = self.name.as_str();
+        let interface_literals = self.implements_interfaces.iter().map(|iface| iface.as_str());
+        let mut union_literals: Vec<&str> = context
+            .union_types
+            .values()
+            .filter(|union_type| union_type.types.contains(&self.name))
+            .map(|union_type| union_type.name.as_str())
+            .collect();
+        union_literals.sort();
+        let fragment_matcher = quote! {
+            match fragment.type_condition {
+                None | Some(#name_literal) #(| Some(#interface_literals))* #(| Some(#union_literals))* => {
+                    result.extend(<#name as ::tokio_gql::coercion::CoerceSelection>::coerce(fragment.selection_set, context)?);
+                }
+                _ => (),
+            }
+        };
+
         quote! {
+            #[allow(deprecated)]
             impl ::tokio_gql::coercion::CoerceSelection for #name {
                 fn coerce(
                     query: &::tokio_gql::graphql_parser::query::SelectionSet,
@@ -22,13 +42,22 @@ impl ImplCoerce for ObjectType {
                     let mut result: Vec<#name> = Vec::new();
 
                     for item in query.items.iter() {
+                        if !::tokio_gql::coercion::is_included(item, context)? {
+                            continue;
+                        }
+
                         match item {
                             ::tokio_gql::graphql_parser::query::Selection::Field(ref field) => {
                                 #field_matchers
                             }
-                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(_) => unimplemented!("fragment spreads are unimplemented"),
-                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(_) => unimplemented!("inline fragments are unimplemented"),
-
+                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(spread) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_spread(spread, context)?;
+                                #fragment_matcher
+                            }
+                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(inline) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_inline(inline);
+                                #fragment_matcher
+                            }
                         }
                     }
 
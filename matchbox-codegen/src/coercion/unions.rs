@@ -4,31 +4,84 @@ use graphql_parser::schema::*;
 use proc_macro2::{Span, Term};
 use quote;
 
-pub fn spread_matchers_for_types<'a>(
+/// Matches on the type condition of `fragment`, a `tokio_gql::coercion::Fragment`. Fragments on the abstract type itself are coerced as that type, and fragments on one of its possible types end up in the corresponding `On*` variant. Fragments on another interface or union apply to the possible types both have in common, so they are coerced once for each of them.
+pub fn fragment_matchers_for_types<'a>(
     name_term: Term,
     types: impl Iterator<Item = &'a String>,
-) -> Vec<quote::Tokens> {
-    types
-        .map(|ty| {
+    context: &DeriveContext,
+) -> quote::Tokens {
+    let name_literal = name_term.as_str();
+    let types: Vec<&String> = types.collect();
+
+    let mut abstract_types: Vec<&String> = context
+        .interface_types
+        .keys()
+        .chain(context.union_types.keys())
+        .filter(|abstract_type| abstract_type.as_str() != name_literal)
+        .collect();
+    abstract_types.sort();
+    let abstract_type_matchers = abstract_types.into_iter().filter_map(|abstract_type| {
+        let common_types: Vec<&String> = context
+            .possible_types(abstract_type)
+            .into_iter()
+            .filter(|ty| types.contains(ty))
+            .collect();
+        if common_types.is_empty() {
+            return None;
+        }
+
+        let coercions = common_types.iter().map(|ty| {
             let variant_term = Term::new(&format!("On{}", ty), Span::call_site());
             let ty_term = Term::new(ty, Span::call_site());
             quote! {
-                if let Some(::tokio_gql::graphql_parser::query::TypeCondition::On(ref name)) = fragment.type_condition {
-                    if name == #ty {
-                        let coerced_inner = <#ty_term as ::tokio_gql::coercion::CoerceSelection>::coerce(&fragment.selection_set, &context);
-                        results.push(#name_term::#variant_term(coerced_inner?));
-                    }
-                }
+                let coerced_inner = <#ty_term as ::tokio_gql::coercion::CoerceSelection>::coerce(fragment.selection_set, &context);
+                results.push(#name_term::#variant_term(coerced_inner?));
+            }
+        });
+        Some(quote! {
+            Some(#abstract_type) => {
+                #(#coercions)*
             }
         })
-        .collect()
+    });
+
+    let type_matchers = types.iter().map(|ty| {
+        let variant_term = Term::new(&format!("On{}", ty), Span::call_site());
+        let ty_term = Term::new(ty, Span::call_site());
+        quote! {
+            Some(#ty) => {
+                let coerced_inner = <#ty_term as ::tokio_gql::coercion::CoerceSelection>::coerce(fragment.selection_set, &context);
+                results.push(#name_term::#variant_term(coerced_inner?));
+            }
+        }
+    });
+
+    quote! {
+        match fragment.type_condition {
+            None | Some(#name_literal) => {
+                results.extend(<#name_term as ::tokio_gql::coercion::CoerceSelection>::coerce(fragment.selection_set, &context)?);
+            }
+            #(#type_matchers)*
+            #(#abstract_type_matchers)*
+            Some(type_condition) => {
+                return Err(::tokio_gql::coercion::CoercionError::new(format!(
+                    "a fragment on {} can never apply to a value of type {}",
+                    type_condition,
+                    #name_literal,
+                )));
+            }
+        }
+    }
 }
 
 impl ImplCoerce for UnionType {
-    fn impl_coerce(&self, _context: &DeriveContext) -> quote::Tokens {
+    fn impl_coerce(&self, context: &DeriveContext) -> quote::Tokens {
         let name_term = Term::new(&self.name, Span::call_site());
+        let name_literal = self.name.as_str();
 
-        let field_matchers = spread_matchers_for_types(name_term.clone(), self.types.iter());
+        let fragment_matchers =
+            fragment_matchers_for_types(name_term.clone(), self.types.iter(), context);
+        let typename_constructor = ::shared::typename_constructor(&name_term);
 
         quote! {
             impl ::tokio_gql::coercion::CoerceSelection for #name_term {
@@ -38,11 +91,30 @@ impl ImplCoerce for UnionType {
                 ) -> Result<Vec<#name_term>, ::tokio_gql::coercion::CoercionError> {
                     let mut results = Vec::<#name_term>::new();
                     for selection in query.items.iter() {
+                        if !::tokio_gql::coercion::is_included(selection, context)? {
+                            continue;
+                        }
+
                         match selection {
-                            ::tokio_gql::graphql_parser::query::Selection::Field(_) => unreachable!("field on union"),
-                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(_) => unimplemented!("fragment spread on union"),
-                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(fragment) => {
-                                #(#field_matchers)*
+                            ::tokio_gql::graphql_parser::query::Selection::Field(field) => {
+                                // `__typename` is the only field that can be selected directly on a union.
+                                if field.name == "__typename" {
+                                    results.push(#typename_constructor);
+                                } else {
+                                    return Err(::tokio_gql::coercion::CoercionError::new(format!(
+                                        "only __typename can be selected on union {}, found {}",
+                                        #name_literal,
+                                        field.name,
+                                    )));
+                                }
+                            }
+                            ::tokio_gql::graphql_parser::query::Selection::FragmentSpread(spread) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_spread(spread, context)?;
+                                #fragment_matchers
+                            }
+                            ::tokio_gql::graphql_parser::query::Selection::InlineFragment(inline) => {
+                                let fragment = ::tokio_gql::coercion::Fragment::from_inline(inline);
+                                #fragment_matchers
                             },
 
                         }
@@ -1,27 +1,30 @@
-use graphql_parser::schema::{EnumType, InputObjectType, InterfaceType, ObjectType,
-                             SchemaDefinition, UnionType};
+use graphql_parser;
+use graphql_parser::schema::{Definition, DirectiveDefinition, EnumType, InputObjectType,
+                             InterfaceType, ObjectType, SchemaDefinition, UnionType};
 use std::collections::{HashMap, HashSet};
 
+/// The directives every schema supports, see https://graphql.org/learn/queries/#directives
+const BUILTIN_DIRECTIVES: &str = include_str!("builtin_directives.graphql");
+
+/// See https://graphql.org/learn/schema/#scalar-types
+const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
 pub struct DeriveContext {
+    pub directives: Vec<DirectiveDefinition>,
     pub enum_types: HashMap<String, EnumType>,
     pub input_types: HashMap<String, InputObjectType>,
     pub interface_types: HashMap<String, InterfaceType>,
     pub object_types: Vec<ObjectType>,
     pub scalar_types: HashSet<String>,
+    /// The Rust types custom scalars map to, when they are not named after the scalar.
+    pub scalar_mappings: HashMap<String, String>,
     pub union_types: HashMap<String, UnionType>,
     schema_type: Option<SchemaDefinition>,
 }
 
 impl DeriveContext {
     pub fn new() -> DeriveContext {
-        let mut scalar_types = HashSet::new();
-
-        // See https://graphql.org/learn/schema/#scalar-types
-        scalar_types.insert("Int".to_string());
-        scalar_types.insert("Float".to_string());
-        scalar_types.insert("String".to_string());
-        scalar_types.insert("Boolean".to_string());
-        scalar_types.insert("ID".to_string());
+        let scalar_types = BUILTIN_SCALARS.iter().map(|s| s.to_string()).collect();
 
         let object_types = Vec::new();
         let input_types = HashMap::new();
@@ -29,12 +32,24 @@ impl DeriveContext {
         let union_types = HashMap::new();
         let interface_types = HashMap::new();
 
+        let directives = graphql_parser::parse_schema(BUILTIN_DIRECTIVES)
+            .expect("the built-in directives are valid")
+            .definitions
+            .into_iter()
+            .filter_map(|definition| match definition {
+                Definition::DirectiveDefinition(directive) => Some(directive),
+                _ => None,
+            })
+            .collect();
+
         DeriveContext {
+            directives,
             enum_types,
             input_types,
             interface_types,
             object_types,
             scalar_types,
+            scalar_mappings: HashMap::new(),
             schema_type: None,
             union_types,
         }
@@ -48,6 +63,10 @@ impl DeriveContext {
         self.schema_type = Some(schema)
     }
 
+    pub fn insert_directive(&mut self, directive: DirectiveDefinition) {
+        self.directives.push(directive);
+    }
+
     pub fn insert_object(&mut self, object_type: ObjectType) {
         self.object_types.push(object_type);
     }
@@ -68,6 +87,15 @@ impl DeriveContext {
         self.scalar_types.contains(type_name)
     }
 
+    /// Scalars declared in the schema, as opposed to the built-in ones.
+    pub fn is_custom_scalar(&self, type_name: &str) -> bool {
+        self.is_scalar(type_name) && !BUILTIN_SCALARS.contains(&type_name)
+    }
+
+    pub fn map_scalar(&mut self, scalar_type: String, rust_type: String) {
+        self.scalar_mappings.insert(scalar_type, rust_type);
+    }
+
     pub fn is_enum(&self, type_name: &str) -> bool {
         self.enum_types.keys().any(|k| k == type_name)
     }
@@ -80,4 +108,22 @@ impl DeriveContext {
         self.interface_types
             .insert(interface_type.name.clone(), interface_type);
     }
+
+    /// The object types a value of an interface or union can have. Other types have none.
+    pub fn possible_types(&self, type_name: &str) -> Vec<&String> {
+        match self.union_types.get(type_name) {
+            Some(union_type) => union_type.types.iter().collect(),
+            None => self
+                .object_types
+                .iter()
+                .filter(|object| {
+                    object
+                        .implements_interfaces
+                        .iter()
+                        .any(|iface| iface == type_name)
+                })
+                .map(|object| &object.name)
+                .collect(),
+        }
+    }
 }
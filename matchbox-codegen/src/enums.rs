@@ -2,13 +2,26 @@ use graphql_parser::schema::EnumType;
 use heck::*;
 use proc_macro2::{Literal, Span, Term};
 use quote;
+use shared;
 
 pub fn gql_enum_to_rs(enum_type: &EnumType) -> quote::Tokens {
     let name = Term::new(enum_type.name.as_str(), Span::call_site());
-    let values: Vec<Term> = enum_type
+    let values: Vec<quote::Tokens> = enum_type
         .values
         .iter()
-        .map(|v| Term::new(v.name.to_camel_case().as_str(), Span::call_site()))
+        .map(|v| {
+            let variant_name = v.name.to_camel_case();
+            let variant = Term::new(variant_name.as_str(), Span::call_site());
+            let deprecated_attr = shared::deprecated_attr(&v.directives);
+            // Variables hold the GraphQL name of the value, which `rename_all` does not always give back.
+            let rename_attr = if variant_name.to_shouty_snake_case() == v.name {
+                quote!()
+            } else {
+                let name_literal = v.name.as_str();
+                quote!(#[serde(rename = #name_literal)])
+            };
+            quote!(#deprecated_attr #rename_attr #variant)
+        })
         .collect();
     let doc_attr: quote::Tokens = if let Some(ref doc_string) = enum_type.description {
         let str_literal = Literal::string(doc_string.as_str());
@@ -76,4 +89,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn enum_derive_with_deprecated_values() {
+        assert_expands_to! {
+            r##"
+            enum BreadKind {
+                WHITE @deprecated(reason: "Not healthy enough")
+                FULL_GRAIN
+            }
+            "## => {
+                #[derive(Debug, PartialEq, Deserialize)]
+                #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+                pub enum BreadKind {
+                    #[deprecated(note = "Not healthy enough")]
+                    White,
+                    FullGrain,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn enum_derive_with_irregular_names() {
+        assert_expands_to! {
+            r##"
+            enum Device {
+                iPhone
+                desktop
+                SMART_TV
+            }
+            "## => {
+                #[derive(Debug, PartialEq, Deserialize)]
+                #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+                pub enum Device {
+                    #[serde(rename = "iPhone")]
+                    IPhone,
+                    #[serde(rename = "desktop")]
+                    Desktop,
+                    SmartTv,
+                }
+            }
+        }
+    }
 }
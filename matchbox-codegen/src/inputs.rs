@@ -4,7 +4,7 @@ use proc_macro2::{Literal, Span, Term};
 use quote;
 use shared;
 
-pub fn gql_input_to_rs(input_type: &InputObjectType, _context: &DeriveContext) -> quote::Tokens {
+pub fn gql_input_to_rs(input_type: &InputObjectType, context: &DeriveContext) -> quote::Tokens {
     let name = Term::new(&input_type.name, Span::call_site());
     let values: Vec<Term> = input_type
         .fields
@@ -14,7 +14,21 @@ pub fn gql_input_to_rs(input_type: &InputObjectType, _context: &DeriveContext) -
     let types: Vec<_> = input_type
         .fields
         .iter()
-        .map(|v| shared::gql_type_to_json_type(&v.value_type))
+        .map(|v| shared::gql_type_to_json_type(&v.value_type, context))
+        .collect();
+    // Custom scalars are deserialized with their `ScalarValue` impl.
+    let attrs: Vec<quote::Tokens> = input_type
+        .fields
+        .iter()
+        .map(|v| {
+            if !context.is_custom_scalar(shared::extract_inner_name(&v.value_type)) {
+                quote!()
+            } else if shared::type_is_optional(&v.value_type) {
+                quote!(#[serde(default, deserialize_with = "::tokio_gql::coercion::deserialize_scalar")])
+            } else {
+                quote!(#[serde(deserialize_with = "::tokio_gql::coercion::deserialize_scalar")])
+            }
+        })
         .collect();
     let doc_attr: quote::Tokens = if let Some(ref doc_string) = input_type.description {
         let str_literal = Literal::string(&doc_string);
@@ -27,7 +41,7 @@ pub fn gql_input_to_rs(input_type: &InputObjectType, _context: &DeriveContext) -
         #doc_attr
         #[derive(Debug, PartialEq, Deserialize)]
         pub struct #name {
-            #(#values: #types),* ,
+            #(#attrs #values: #types),* ,
         }
     }
 }
@@ -61,4 +75,35 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn input_object_derive_with_custom_scalars() {
+        let parsed = ::graphql_parser::parse_schema(
+            r##"
+            scalar DateTime
+            scalar Url
+
+            input Event {
+                startsAt: DateTime!
+                link: Url
+            }
+            "##,
+        )
+        .unwrap();
+        let mut context = DeriveContext::new();
+        ::extract_definitions(&parsed, &mut context);
+        context.map_scalar("DateTime".to_string(), "chrono::DateTime<Utc>".to_string());
+
+        let got = super::gql_input_to_rs(&context.input_types["Event"], &context);
+        let expected = quote! {
+            #[derive(Debug, PartialEq, Deserialize)]
+            pub struct Event {
+                #[serde(deserialize_with = "::tokio_gql::coercion::deserialize_scalar")]
+                startsAt: chrono::DateTime<Utc>,
+                #[serde(default, deserialize_with = "::tokio_gql::coercion::deserialize_scalar")]
+                link: Option<Url>,
+            }
+        };
+        assert_eq!(expected, got);
+    }
 }
@@ -72,6 +72,7 @@ mod tests {
                 WithWittyComment { respond: CharacterWithWittyCommentResponder, meh: Option<bool>, },
                 Friends { respond: CharacterFriendsResponder, selection: Vec<Character>, },
                 AppearsIn { respond: CharacterAppearsInResponder, selection: Vec<Episode>, },
+                __Typename { respond: ::tokio_gql::response::TypenameResponder, },
             }
         }
         }
@@ -97,12 +98,14 @@ mod tests {
                 Id { respond: WookieIdResponder, },
                 Name { respond: WookieNameResponder, },
                 Hairiness { respond: WookieHairinessResponder, },
+                __Typename { respond: ::tokio_gql::response::TypenameResponder, },
             }
 
             #[derive(Debug, PartialEq)]
             pub enum Character {
                 Id { respond: CharacterIdResponder, },
                 Name { respond: CharacterNameResponder, },
+                __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 OnWookie(Vec<Wookie>),
             }
         }
@@ -1,9 +1,54 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
+use proc_macro2::{Span, Term};
 use quote;
 
 impl Introspectable for schema::DirectiveDefinition {
-    fn introspect(&self) -> quote::Tokens {
-        unimplemented!();
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
+        let name_lit = &self.name;
+        let description = match &self.description {
+            Some(lit) => quote!(Some(#lit)),
+            None => quote!(None),
+        };
+        let locations = self.locations.iter().map(|location| {
+            let variant = Term::new(location_variant(location), Span::call_site());
+            quote!(::tokio_gql::introspection::DirectiveLocation::#variant)
+        });
+        let args = self.arguments.iter().map(|arg| arg.introspect(context));
+
+        quote! {
+            ::tokio_gql::introspection::Directive {
+                name: #name_lit,
+                description: #description,
+                locations: &[#(#locations),*],
+                args: &[#(#args),*],
+            }
+        }
+    }
+}
+
+fn location_variant(location: &schema::DirectiveLocation) -> &'static str {
+    use graphql_parser::schema::DirectiveLocation::*;
+
+    match location {
+        Query => "Query",
+        Mutation => "Mutation",
+        Subscription => "Subscription",
+        Field => "Field",
+        FragmentDefinition => "FragmentDefinition",
+        FragmentSpread => "FragmentSpread",
+        InlineFragment => "InlineFragment",
+        Schema => "Schema",
+        Scalar => "Scalar",
+        Object => "Object",
+        FieldDefinition => "FieldDefinition",
+        ArgumentDefinition => "ArgumentDefinition",
+        Interface => "Interface",
+        Union => "Union",
+        Enum => "Enum",
+        EnumValue => "EnumValue",
+        InputObject => "InputObject",
+        InputFieldDefinition => "InputFieldDefinition",
     }
 }
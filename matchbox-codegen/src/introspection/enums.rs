@@ -1,14 +1,16 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::EnumType {
-    fn introspect(&self) -> quote::Tokens {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
         let name_lit = &self.name;
         let description = match &self.description {
             Some(lit) => quote!(Some(#lit)),
             None => quote!(None),
         };
+        let values = self.values.iter().map(|value| value.introspect(context));
 
         quote!{
             ::tokio_gql::introspection::Type {
@@ -16,6 +18,33 @@ impl Introspectable for schema::EnumType {
                 name: Some(#name_lit),
                 description: #description,
                 fields: &[],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[#(#values),*],
+                input_fields: &[],
+            }
+        }
+    }
+}
+
+impl Introspectable for schema::EnumValue {
+    fn introspect(&self, _context: &DeriveContext) -> quote::Tokens {
+        let name_lit = &self.name;
+        let description = match &self.description {
+            Some(lit) => quote!(Some(#lit)),
+            None => quote!(None),
+        };
+        let (is_deprecated, deprecation_reason) = match ::shared::deprecation_reason(&self.directives) {
+            Some(reason) => (true, quote!(Some(#reason))),
+            None => (false, quote!(None)),
+        };
+
+        quote! {
+            ::tokio_gql::introspection::EnumValue {
+                name: #name_lit,
+                description: #description,
+                is_deprecated: #is_deprecated,
+                deprecation_reason: #deprecation_reason,
             }
         }
     }
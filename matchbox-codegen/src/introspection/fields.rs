@@ -1,25 +1,30 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::Field {
-    fn introspect(&self) -> quote::Tokens {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
         let name_lit = &self.name;
         let description = match &self.description {
             Some(lit) => quote!(Some(#lit)),
             None => quote!(None),
         };
-        let args = self.arguments.iter().map(|arg| arg.introspect());
-        let type_name = ::shared::extract_inner_name(&self.field_type);
+        let args = self.arguments.iter().map(|arg| arg.introspect(context));
+        let field_type = self.field_type.introspect(context);
+        let (is_deprecated, deprecation_reason) = match ::shared::deprecation_reason(&self.directives) {
+            Some(reason) => (true, quote!(Some(#reason))),
+            None => (false, quote!(None)),
+        };
 
         quote! {
             ::tokio_gql::introspection::Field {
                 name: #name_lit,
                 description: #description,
                 args: &[#(#args),*],
-                type_: #type_name,
-                is_deprecated: false,
-                deprecation_reason: None,
+                type_: #field_type,
+                is_deprecated: #is_deprecated,
+                deprecation_reason: #deprecation_reason,
             }
         }
     }
@@ -1,25 +1,27 @@
-use super::input_values::AsField;
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::InputObjectType {
-    fn introspect(&self) -> quote::Tokens {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
         let name_lit = &self.name;
         let description = match &self.description {
             Some(lit) => quote!(Some(#lit)),
             None => quote!(None),
         };
-        let fields = self.fields
-            .iter()
-            .map(|field| AsField(field.clone()).introspect());
+        let input_fields = self.fields.iter().map(|field| field.introspect(context));
 
         quote! {
             ::tokio_gql::introspection::Type {
                 kind: ::tokio_gql::introspection::TypeKind::InputObject,
                 name: Some(#name_lit),
                 description: #description,
-                fields: &[#(#fields),*],
+                fields: &[],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[#(#input_fields),*],
             }
         }
     }
@@ -1,15 +1,16 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::InputValue {
-    fn introspect(&self) -> quote::Tokens {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
         let name = &self.name;
         let description = match &self.description {
             Some(lit) => quote!(Some(#lit)),
             None => quote!(None),
         };
-        let type_name = ::shared::extract_inner_name(&self.value_type);
+        let value_type = self.value_type.introspect(context);
         let default_value = match &self.default_value {
             Some(value) => {
                 let inner = format!("{}", value);
@@ -21,32 +22,9 @@ impl Introspectable for schema::InputValue {
             ::tokio_gql::introspection::InputValue {
                 name: #name,
                 description: #description,
-                type_: #type_name,
+                type_: #value_type,
                 default_value: #default_value,
             }
         }
     }
 }
-
-pub(crate) struct AsField(pub schema::InputValue);
-
-impl Introspectable for AsField {
-    fn introspect(&self) -> quote::Tokens {
-        let name = &self.0.name;
-        let description = match &self.0.description {
-            Some(lit) => quote!(Some(#lit)),
-            None => quote!(None),
-        };
-        let type_name = ::shared::extract_inner_name(&self.0.value_type);
-        quote! {
-            ::tokio_gql::introspection::Field {
-                name: #name,
-                description: #description,
-                args: &[],
-                type_: #type_name,
-                is_deprecated: false,
-                deprecation_reason: None,
-            }
-        }
-    }
-}
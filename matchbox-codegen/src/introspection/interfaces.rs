@@ -1,9 +1,33 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::InterfaceType {
-    fn introspect(&self) -> quote::Tokens {
-        unimplemented!();
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
+        let name_lit = &self.name;
+        let description = match &self.description {
+            Some(lit) => quote!(Some(#lit)),
+            None => quote!(None),
+        };
+        let fields = self.fields.iter().map(|field| field.introspect(context));
+        let implementors = context
+            .object_types
+            .iter()
+            .filter(|object| object.implements_interfaces.contains(&self.name))
+            .map(|object| object.name.as_str());
+
+        quote! {
+            ::tokio_gql::introspection::Type {
+                kind: ::tokio_gql::introspection::TypeKind::Interface,
+                name: Some(#name_lit),
+                description: #description,
+                fields: &[#(#fields),*],
+                interfaces: &[],
+                possible_types: &[#(#implementors),*],
+                enum_values: &[],
+                input_fields: &[],
+            }
+        }
     }
 }
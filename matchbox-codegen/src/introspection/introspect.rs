@@ -1,57 +1,72 @@
 use super::traits::Introspectable;
 use context::DeriveContext;
+use graphql_parser::schema::SchemaDefinition;
 use quote;
 
 pub fn introspect_context(context: &DeriveContext) -> quote::Tokens {
     let mut types = Vec::new();
 
     for object in context.object_types.iter() {
-        types.push(object.introspect());
+        types.push(object.introspect(context));
     }
 
-    for enum_type in context.enum_types.values() {
-        types.push(enum_type.introspect());
+    // The other definitions are stored in hash maps, they are sorted by name so the generated code is stable.
+    let mut interface_types: Vec<_> = context.interface_types.values().collect();
+    interface_types.sort_by_key(|interface_type| &interface_type.name);
+
+    for interface_type in interface_types {
+        types.push(interface_type.introspect(context));
     }
 
-    for input in context.input_types.values() {
-        types.push(input.introspect());
+    let mut union_types: Vec<_> = context.union_types.values().collect();
+    union_types.sort_by_key(|union_type| &union_type.name);
+
+    for union_type in union_types {
+        types.push(union_type.introspect(context));
     }
 
-    let schema = match context.get_schema() {
-        Some(schema) => {
-            let query_type = match schema.query {
-                Some(name) => quote!(Some(#name)),
-                None => quote!(None),
-            };
-            let mutation_type = match schema.mutation {
-                Some(name) => quote!(Some(#name)),
-                None => quote!(None),
-            };
-            let subscription_type = match schema.subscription {
-                Some(name) => quote!(Some(#name)),
-                None => quote!(None),
-            };
+    let mut enum_types: Vec<_> = context.enum_types.values().collect();
+    enum_types.sort_by_key(|enum_type| &enum_type.name);
 
-            quote! {
-                ::tokio_gql::introspection::Schema {
-                    types: INTROSPECTION_TYPES,
-                    query_type: #query_type,
-                    mutation_type: #mutation_type,
-                    subscription_type: #subscription_type,
-                    directives: &[],
-                }
-            }
-        }
-        None => quote! {
-            ::tokio_gql::introspection::Schema {
-                types: INTROSPECTION_TYPES,
-                query_type: None,
-                mutation_type: None,
-                subscription_type: None,
-                directives: &[],
+    for enum_type in enum_types {
+        types.push(enum_type.introspect(context));
+    }
+
+    let mut input_types: Vec<_> = context.input_types.values().collect();
+    input_types.sort_by_key(|input_type| &input_type.name);
+
+    for input in input_types {
+        types.push(input.introspect(context));
+    }
+
+    let mut scalar_names: Vec<&String> = context.scalar_types.iter().collect();
+    scalar_names.sort();
+
+    for scalar_name in scalar_names {
+        types.push(quote! {
+            ::tokio_gql::introspection::Type {
+                kind: ::tokio_gql::introspection::TypeKind::Scalar,
+                name: Some(#scalar_name),
+                description: None,
+                fields: &[],
+                interfaces: &[],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[],
             }
-        },
-    };
+        });
+    }
+
+    let schema = context
+        .get_schema()
+        .unwrap_or_else(|| SchemaDefinition {
+            position: Default::default(),
+            directives: Vec::new(),
+            query: None,
+            mutation: None,
+            subscription: None,
+        })
+        .introspect(context);
 
     quote! {
         #[allow(dead_code)]
@@ -59,7 +74,8 @@ pub fn introspect_context(context: &DeriveContext) -> quote::Tokens {
             #(#types),*
         ];
 
+        /// The introspection data for the schema, see the `introspection` crate.
         #[allow(dead_code)]
-        const INTROSPECTION_SCHEMA: ::tokio_gql::introspection::Schema = #schema;
+        pub const INTROSPECTION_SCHEMA: ::tokio_gql::introspection::Schema = #schema;
     }
 }
@@ -8,4 +8,5 @@ pub mod introspect;
 mod objects;
 mod schema;
 pub mod traits;
+mod type_refs;
 mod unions;
@@ -1,22 +1,28 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::ObjectType {
-    fn introspect(&self) -> quote::Tokens {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
         let name_lit = &self.name;
         let description = match &self.description {
             Some(lit) => quote!(Some(#lit)),
             None => quote!(None),
         };
-        let fields = self.fields.iter().map(|field| field.introspect());
+        let fields = self.fields.iter().map(|field| field.introspect(context));
+        let interfaces = &self.implements_interfaces;
 
         quote! {
             ::tokio_gql::introspection::Type {
                 kind: ::tokio_gql::introspection::TypeKind::Object,
                 name: Some(#name_lit),
                 description: #description,
-                fields: &[#(#fields),*]
+                fields: &[#(#fields),*],
+                interfaces: &[#(#interfaces),*],
+                possible_types: &[],
+                enum_values: &[],
+                input_fields: &[],
             }
         }
     }
@@ -1,9 +1,35 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::SchemaDefinition {
-    fn introspect(&self) -> quote::Tokens {
-        unimplemented!();
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
+        let query_type = match &self.query {
+            Some(name) => quote!(Some(#name)),
+            None => quote!(None),
+        };
+        let mutation_type = match &self.mutation {
+            Some(name) => quote!(Some(#name)),
+            None => quote!(None),
+        };
+        let subscription_type = match &self.subscription {
+            Some(name) => quote!(Some(#name)),
+            None => quote!(None),
+        };
+        let directives = context
+            .directives
+            .iter()
+            .map(|directive| directive.introspect(context));
+
+        quote! {
+            ::tokio_gql::introspection::Schema {
+                types: INTROSPECTION_TYPES,
+                query_type: #query_type,
+                mutation_type: #mutation_type,
+                subscription_type: #subscription_type,
+                directives: &[#(#directives),*],
+            }
+        }
     }
 }
@@ -0,0 +1,45 @@
+use super::traits::Introspectable;
+use context::DeriveContext;
+use graphql_parser::schema;
+use quote;
+
+impl Introspectable for schema::Type {
+    fn introspect(&self, context: &DeriveContext) -> quote::Tokens {
+        match self {
+            schema::Type::NamedType(name) => {
+                quote!(::tokio_gql::introspection::TypeRef::Named(#name))
+            }
+            schema::Type::ListType(inner) => {
+                let inner = inner.introspect(context);
+                quote!(::tokio_gql::introspection::TypeRef::List(&#inner))
+            }
+            schema::Type::NonNullType(inner) => {
+                let inner = inner.introspect(context);
+                quote!(::tokio_gql::introspection::TypeRef::NonNull(&#inner))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_type_ref() {
+        let field_type = schema::Type::NonNullType(Box::new(schema::Type::ListType(Box::new(
+            schema::Type::NonNullType(Box::new(schema::Type::NamedType("Character".to_string()))),
+        ))));
+
+        assert_eq!(
+            field_type.introspect(&DeriveContext::new()).to_string(),
+            quote!(::tokio_gql::introspection::TypeRef::NonNull(
+                &::tokio_gql::introspection::TypeRef::List(
+                    &::tokio_gql::introspection::TypeRef::NonNull(
+                        &::tokio_gql::introspection::TypeRef::Named("Character")
+                    )
+                )
+            )).to_string()
+        );
+    }
+}
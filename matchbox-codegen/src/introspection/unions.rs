@@ -1,9 +1,28 @@
 use super::traits::Introspectable;
+use context::DeriveContext;
 use graphql_parser::schema;
 use quote;
 
 impl Introspectable for schema::UnionType {
-    fn introspect(&self) -> quote::Tokens {
-        unimplemented!();
+    fn introspect(&self, _context: &DeriveContext) -> quote::Tokens {
+        let name_lit = &self.name;
+        let description = match &self.description {
+            Some(lit) => quote!(Some(#lit)),
+            None => quote!(None),
+        };
+        let possible_types = &self.types;
+
+        quote! {
+            ::tokio_gql::introspection::Type {
+                kind: ::tokio_gql::introspection::TypeKind::Union,
+                name: Some(#name_lit),
+                description: #description,
+                fields: &[],
+                interfaces: &[],
+                possible_types: &[#(#possible_types),*],
+                enum_values: &[],
+                input_fields: &[],
+            }
+        }
     }
 }
@@ -25,11 +25,26 @@ mod unions;
 use coercion::*;
 use context::DeriveContext;
 use proc_macro2::{Span, Term};
+use std::collections::HashMap;
 
-pub fn expand_schema(schema: &str) -> quote::Tokens {
-    let schema = graphql_parser::parse_schema(&schema).expect("invalid schema");
+pub fn expand_schema(schema_string: &str) -> quote::Tokens {
+    expand_schema_with_scalars(schema_string, &HashMap::new())
+}
+
+/// Like `expand_schema`, with the Rust types (as paths, like `"chrono::DateTime<Utc>"`) some custom scalars map to.
+pub fn expand_schema_with_scalars(
+    schema_string: &str,
+    scalars: &HashMap<String, String>,
+) -> quote::Tokens {
+    let schema = graphql_parser::parse_schema(&schema_string).expect("invalid schema");
     let mut context = DeriveContext::new();
     extract_definitions(&schema, &mut context);
+    for (scalar_name, rust_type) in scalars.iter() {
+        if !context.is_custom_scalar(scalar_name) {
+            panic!("{} is not a custom scalar of the schema", scalar_name);
+        }
+        context.map_scalar(scalar_name.to_string(), rust_type.to_string());
+    }
     let mut definitions = Vec::new();
     gql_document_to_rs(&mut definitions, &context);
     let coerce_impls = coerce_impls(&context);
@@ -39,6 +54,9 @@ pub fn expand_schema(schema: &str) -> quote::Tokens {
     let responder_impls = responders::gather_impls::gather_impls(&context);
 
     quote! {
+        /// The schema the types in this module were derived from.
+        pub const THE_SCHEMA: &'static str = #schema_string;
+
         #(#definitions)*
 
         #(#coerce_impls)*
@@ -119,7 +137,9 @@ fn extract_definitions(document: &graphql_parser::schema::Document, context: &mu
                     context.insert_interface(interface_type.clone());
                 }
             },
-            Definition::DirectiveDefinition(_) => unimplemented!("directive definition"),
+            Definition::DirectiveDefinition(directive_definition) => {
+                context.insert_directive(directive_definition.clone())
+            }
             Definition::SchemaDefinition(schema_definition) => {
                 context.set_schema(schema_definition.clone())
             }
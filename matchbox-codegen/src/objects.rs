@@ -27,12 +27,13 @@ pub fn gql_type_to_rs(
     )
 }
 
+/// The field variants of an object or interface selection, including the `__typename` meta-field.
 pub fn get_field_names<'a>(
     fields: impl IntoIterator<Item = &'a graphql_parser::schema::Field>,
     context: &DeriveContext,
     object_name: &Term,
 ) -> Vec<quote::Tokens> {
-    fields
+    let mut field_names: Vec<quote::Tokens> = fields
         .into_iter()
         .map(|f| {
             let ident = Term::new(&f.name.to_camel_case(), Span::call_site());
@@ -41,7 +42,7 @@ pub fn get_field_names<'a>(
                 .map(|arg| {
                     let field_name =
                         Term::new(arg.name.to_mixed_case().as_str(), Span::call_site());
-                    let field_type = shared::gql_type_to_json_type(&arg.value_type);
+                    let field_type = shared::gql_type_to_json_type(&arg.value_type, context);
                     quote!( #field_name: #field_type )
                 })
                 .collect();
@@ -60,11 +61,17 @@ pub fn get_field_names<'a>(
                 Span::call_site(),
             );
 
+            let deprecated_attr = shared::deprecated_attr(&f.directives);
+
             quote!{
+                #deprecated_attr
                 #ident { respond: #responder_type, #sub_field_set #(#args,)* }
             }
         })
-        .collect()
+        .collect();
+
+    field_names.push(shared::typename_variant());
+    field_names
 }
 
 #[cfg(test)]
@@ -81,7 +88,21 @@ mod tests {
             }
             "# => {
                 #[derive(Debug, PartialEq)]
-                pub enum Pasta { Shape { respond: PastaShapeResponder, }, Ingredients { respond: PastaIngredientsResponder, }, }
+                pub enum Pasta { Shape { respond: PastaShapeResponder, }, Ingredients { respond: PastaIngredientsResponder, }, __Typename { respond: ::tokio_gql::response::TypenameResponder, }, }
+            }
+        }
+    }
+
+    #[test]
+    fn object_derive_with_typename_field() {
+        assert_expands_to! {
+            r#"
+            type Pasta {
+                typename: String!
+            }
+            "# => {
+                #[derive(Debug, PartialEq)]
+                pub enum Pasta { Typename { respond: PastaTypenameResponder, }, __Typename { respond: ::tokio_gql::response::TypenameResponder, }, }
             }
         }
     }
@@ -99,6 +120,30 @@ mod tests {
                 pub enum Pasta {
                     Shape { respond: PastaShapeResponder, strict: Option<bool>, },
                     Ingredients { respond: PastaIngredientsResponder, filter: String, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn object_derive_with_deprecated_fields() {
+        assert_expands_to! {
+            r#"
+            type Pasta {
+                shape: String! @deprecated(reason: "Use `form` instead.")
+                form: String!
+                cookingTime: Int @deprecated
+            }
+            "# => {
+                #[derive(Debug, PartialEq)]
+                pub enum Pasta {
+                    #[deprecated(note = "Use `form` instead.")]
+                    Shape { respond: PastaShapeResponder, },
+                    Form { respond: PastaFormResponder, },
+                    #[deprecated(note = "No longer supported")]
+                    CookingTime { respond: PastaCookingTimeResponder, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 }
             }
         }
@@ -118,7 +163,7 @@ mod tests {
             "## => {
                 #[doc = "Represents a point on the plane.\n"]
                 #[derive(Debug, PartialEq)]
-                pub enum Point { X { respond: PointXResponder, }, Y { respond: PointYResponder, }, }
+                pub enum Point { X { respond: PointXResponder, }, Y { respond: PointYResponder, }, __Typename { respond: ::tokio_gql::response::TypenameResponder, }, }
             }
         }
     }
@@ -147,12 +192,14 @@ mod tests {
                 pub enum DessertDescriptor {
                     Name { respond: DessertDescriptorNameResponder, },
                     ContainsChocolate { respond: DessertDescriptorContainsChocolateResponder, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 }
 
                 #[derive(Debug, PartialEq)]
                 pub enum Cheese {
                     Name { respond: CheeseNameResponder, },
                     Blue { respond: CheeseBlueResponder, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 }
 
                 #[derive(Debug, PartialEq)]
@@ -160,6 +207,7 @@ mod tests {
                     MainCourse { respond: MealMainCourseResponder, },
                     Cheese { respond: MealCheeseResponder, selection: Vec<Cheese>, vegan: Option<bool>, },
                     Dessert { respond: MealDessertResponder, selection: Vec<DessertDescriptor>, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 }
             }
         }
@@ -192,6 +240,7 @@ mod tests {
                     Ingredients { respond: PastaIngredientsResponder, filter: String, },
                     Instructions { respond: PastaInstructionsResponder, filter: Option<CookingInstructions>, },
                     Name { respond: PastaNameResponder, },
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                 }
 
                 #[derive(Debug, PartialEq, Deserialize)]
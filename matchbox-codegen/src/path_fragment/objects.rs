@@ -4,7 +4,6 @@ use graphql_parser;
 use heck::*;
 use proc_macro2::{Span, Term};
 use quote;
-use shared;
 
 impl ImplPathFragment for graphql_parser::schema::ObjectType {
     fn impl_path_fragment(&self, _context: &DeriveContext) -> quote::Tokens {
@@ -12,15 +11,16 @@ impl ImplPathFragment for graphql_parser::schema::ObjectType {
         let variant_matchers = self.fields.iter().map(|field| {
             let term = Term::new(&field.name.to_camel_case(), Span::call_site());
 
-            quote!(#object_name::#term { .. })
+            quote!(#object_name::#term { respond, .. })
         });
-        let name_literals = self.fields.iter().map(|field| &field.name);
 
         quote! {
+            #[allow(deprecated)]
             impl ::tokio_gql::response::PathFragment for #object_name {
-                fn as_path_fragment(&self) -> &'static str {
+                fn as_path_fragment(&self) -> &str {
                     match self {
-                        #(#variant_matchers => #name_literals),*
+                        #(#variant_matchers => respond.response_key(),)*
+                        #object_name::__Typename { respond } => respond.response_key()
                     }
                 }
             }
@@ -52,11 +52,13 @@ mod tests {
         }.unwrap();
         let context = DeriveContext::new();
         let expected = quote! {
+            #[allow(deprecated)]
             impl ::tokio_gql::response::PathFragment for FriendsEdge {
-                fn as_path_fragment(&self) -> &'static str {
+                fn as_path_fragment(&self) -> &str {
                     match self {
-                        FriendsEdge::Cursor { .. } => "cursor",
-                        FriendsEdge::Node { .. } => "node"
+                        FriendsEdge::Cursor { respond, .. } => respond.response_key(),
+                        FriendsEdge::Node { respond, .. } => respond.response_key(),
+                        FriendsEdge::__Typename { respond } => respond.response_key()
                     }
                 }
             }
@@ -1,7 +1,6 @@
-use super::traits::ImplResponder;
 use context::DeriveContext;
 use graphql_parser::schema;
-use heck::*;
+use heck::CamelCase;
 use proc_macro2::{Span, Term};
 use quote;
 
@@ -24,6 +23,29 @@ use quote;
 //     }
 // }
 
+/// The match arms giving the response key of each field variant of `type_name`, and the name of the field as `Type.field` if its type is non-null. This is what the object responders need to record errors and bubble nulls up.
+pub(crate) fn response_entry_arms(type_name: &str, fields: &[schema::Field]) -> Vec<quote::Tokens> {
+    let type_term = Term::new(type_name, Span::call_site());
+
+    fields
+        .iter()
+        .map(|field| {
+            let variant = Term::new(&field.name.to_camel_case(), Span::call_site());
+            let non_null = match field.field_type {
+                schema::Type::NonNullType(_) => {
+                    let description = format!("{}.{}", type_name, field.name);
+                    quote!(Some(#description))
+                }
+                _ => quote!(None),
+            };
+
+            quote! {
+                #type_term::#variant { respond, .. } => (respond.response_key().to_string(), #non_null),
+            }
+        })
+        .collect()
+}
+
 pub(crate) fn impl_field(
     responder_name: &str,
     field_name: &str,
@@ -31,83 +53,49 @@ pub(crate) fn impl_field(
     context: &DeriveContext,
 ) -> quote::Tokens {
     let responder_name = Term::new(responder_name, Span::call_site());
-    field_impl_inner(&responder_name, field_name, ty, context, false)
+    let struct_impl = field_impl_inner(&responder_name, ty, context, false);
+
+    quote! {
+        #[derive(Debug, PartialEq, Default)]
+        pub struct #responder_name {
+            /// The alias of the field in the query, if any.
+            pub alias: Option<String>,
+        }
+
+        impl #responder_name {
+            /// The key of the field in the response: its alias if it has one, its name otherwise.
+            pub fn response_key(&self) -> &str {
+                self.alias.as_ref().map(|alias| alias.as_str()).unwrap_or(#field_name)
+            }
+        }
+
+        #struct_impl
+    }
 }
 
 fn field_impl_inner(
     responder_name: &Term,
-    field_name: &str,
     ty: &schema::Type,
     context: &DeriveContext,
     non_nullable: bool,
 ) -> quote::Tokens {
-    let responder_struct = quote! {
-        #[derive(Debug, PartialEq)]
-        pub struct #responder_name;
-        trivial_default_impl!(#responder_name, #responder_name);
-    };
-
-    let struct_impl = match ty {
-        schema::Type::NonNullType(inner) => {
-            field_impl_inner(responder_name, field_name, inner, context, true)
-        }
+    match ty {
+        schema::Type::NonNullType(inner) => field_impl_inner(responder_name, inner, context, true),
         schema::Type::ListType(inner) => list_responder_impl(responder_name, inner),
         schema::Type::NamedType(name) => {
             if context.is_scalar(&name) {
-                scalar_responder_impl(responder_name, field_name, name, context, non_nullable)
+                scalar_responder_impl(responder_name, name, context, non_nullable)
             } else if context.is_enum(&name) {
                 quote!{}
+            } else if context.object_types.iter().any(|ty| ty.name == name.as_str()) {
+                object_responder_impl(responder_name, name)
+            } else if context.interface_types.contains_key(name.as_str())
+                || context.union_types.contains_key(name.as_str())
+            {
+                abstract_responder_impl(responder_name, name)
             } else {
-                // let object_responder_name = Term::new(
-                //     &::shared::schema_name_to_responder_name(name),
-                //     Span::call_site(),
-                // );
-                // quote!{
-                //     impl #responder_name {
-                //         pub fn to(selection: ..., resolver: ...) -> impl ::futures::Future<Item = (), Error = ::tokio_gql::errors::ResolverError> {
-                //             #object_responder_name::default().to(selection, resolver).and_then(|json| {
-                //                 (..., json)
-                //             })
-                //         }
-                //     }
-                //     // #object_responder_name;
-                // }
-                let object_type = context
-                    .object_types
-                    .iter()
-                    .find(|ty| ty.name == name.as_str());
-                let interface_type = context
-                    .interface_types
-                    .values()
-                    .find(|ty| ty.name == name.as_str());
-                let union_type = context
-                    .union_types
-                    .values()
-                    .find(|ty| ty.name == name.as_str());
-                if object_type.is_none() && interface_type.is_none() && union_type.is_none() {
-                    panic!("No declaration found for field type {}", name);
-                }
-
-                if let Some(object_type) = object_type {
-                    return object_type.impl_responder(context);
-                }
-
-                if let Some(interface_type) = interface_type {
-                    return interface_type.impl_responder(context);
-                }
-
-                if let Some(union_type) = union_type {
-                    return union_type.impl_responder(context);
-                }
-
-                unreachable!();
+                panic!("No declaration found for field type {}", name);
             }
-        };
-
-        quote! {
-            #responder_struct
-
-            #struct_impl
         }
     }
 }
@@ -127,7 +115,6 @@ fn list_responder_impl(responder_name: &Term, inner_type: &schema::Type) -> quot
 
 fn scalar_responder_impl(
     responder_name: &Term,
-    field_name: &str,
     ty: &str,
     context: &DeriveContext,
     non_nullable: bool,
@@ -141,20 +128,80 @@ fn scalar_responder_impl(
     quote! {
         impl #responder_name {
             pub fn with(&self, value: #rust_ty) -> ::tokio_gql::response::Response {
-                ::tokio_gql::response::Response::Immediate((#field_name , <#rust_ty as ::tokio_gql::traits::IntoJson>::into_json(value)))
+                ::tokio_gql::response::Response::Immediate((
+                    self.response_key().to_string(),
+                    <#rust_ty as ::tokio_gql::traits::IntoJson>::into_json(value),
+                ))
             }
         }
     }
 }
 
-fn object_responder_impl() -> quote::Tokens {
-    quote!{}
-}
+/// Delegates to the responder of the object type, and puts the result under the response key of the field.
+fn object_responder_impl(responder_name: &Term, object_name: &str) -> quote::Tokens {
+    let object_responder_name = Term::new(
+        &::shared::schema_name_to_responder_name(object_name),
+        Span::call_site(),
+    );
+    let object_name = Term::new(object_name, Span::call_site());
 
-fn interface_responder_impl() -> quote::Tokens {
-    quote!{}
+    quote! {
+        impl #responder_name {
+            pub fn to<LoaderFuture, Loader, Data, Resolver>(
+                &self,
+                selection: Vec<#object_name>,
+                loader: Loader,
+                resolver: Resolver,
+            ) -> ::tokio_gql::response::Response
+            where
+                Loader: Fn(&[#object_name]) -> LoaderFuture,
+                LoaderFuture: ::futures::Future<Item = Data, Error = ::tokio_gql::errors::ResolverError> + 'static,
+                Data: 'static,
+                Resolver: Fn(#object_name, &Data) -> ::tokio_gql::response::Response + 'static,
+            {
+                use ::futures::prelude::Future;
+
+                let response_key = self.response_key().to_string();
+
+                ::tokio_gql::response::Response::Async(Box::new(
+                    #object_responder_name::default()
+                        .to(selection, loader, resolver)
+                        .map(move |value| (response_key, value))
+                ))
+            }
+        }
+    }
 }
 
-fn union_responder_impl() -> quote::Tokens {
-    quote!{}
+/// Delegates to the responder of the interface or union type. The concrete type of the value has to be known to respond to `__typename` and fragments.
+fn abstract_responder_impl(responder_name: &Term, abstract_name: &str) -> quote::Tokens {
+    let abstract_responder_name = Term::new(
+        &::shared::schema_name_to_responder_name(abstract_name),
+        Span::call_site(),
+    );
+    let abstract_name = Term::new(abstract_name, Span::call_site());
+
+    quote! {
+        impl #responder_name {
+            pub fn to<Resolver>(
+                &self,
+                typename: &str,
+                selection: Vec<#abstract_name>,
+                resolver: Resolver,
+            ) -> ::tokio_gql::response::Response
+            where
+                Resolver: Fn(#abstract_name) -> ::tokio_gql::response::Response,
+            {
+                use ::futures::prelude::Future;
+
+                let response_key = self.response_key().to_string();
+
+                ::tokio_gql::response::Response::Async(Box::new(
+                    #abstract_responder_name::default()
+                        .to(typename, selection, resolver)
+                        .map(move |value| (response_key, value))
+                ))
+            }
+        }
+    }
 }
@@ -13,6 +13,7 @@ impl ImplResponder for schema::ObjectType {
         );
         let variant_name = Term::new(&self.name.to_camel_case(), Span::call_site());
         let name = &self.name;
+        let entry_arms = super::fields::response_entry_arms(name, &self.fields);
         let field_impls = self.fields.iter().map(|field| {
             let field_responder_name = format!("{}{}Responder", name, field.name.to_camel_case());
             super::fields::impl_field(
@@ -39,45 +40,42 @@ impl ImplResponder for schema::ObjectType {
             pub struct #responder_name;
             trivial_default_impl!(#responder_name, #responder_name);
 
+            #[allow(deprecated)]
             impl #responder_name {
+                /// Resolves the fields of the selection, once the data is loaded. A field that fails is null and its error is reported, instead of failing the whole object.
                 pub fn to<LoaderFuture, Loader, Data, Resolver>(
                     &self,
                     selection: Vec<#variant_name>,
                     loader: Loader,
                     resolver: Resolver,
-                ) -> Box<::futures::Future<Item = ::serde_json::Value, Error = ::tokio_gql::errors::ResolverError>
+                ) -> Box<::futures::Future<Item = ::tokio_gql::response::Resolved, Error = ::tokio_gql::errors::ResolverError>>
                 where
                     Loader: Fn(&[#variant_name]) -> LoaderFuture,
-                    LoaderFuture: ::futures::Future<Item = Data, Error = ::tokio_gql::errors::ResolverError>,
-                    Resolver: Fn(#variant_name, &Data) -> ::tokio_gql::response::Response {
+                    LoaderFuture: ::futures::Future<Item = Data, Error = ::tokio_gql::errors::ResolverError> + 'static,
+                    Data: 'static,
+                    Resolver: Fn(#variant_name, &Data) -> ::tokio_gql::response::Response + 'static {
 
                     use ::futures::prelude::Future;
 
-                    loader(&selection).and_then(move |data| {
-                        let mut result = ::serde_json::Map::with_capacity(selection.len());
-                        let mut async_fields: Vec<_> = Vec::new();
+                    Box::new(loader(&selection).and_then(move |data| {
+                        let mut object = ::tokio_gql::response::ObjectResponse::new();
 
                         for field in selection.into_iter() {
-                            let response = resolver(&data, field);
-                            match response {
-                                ::tokio_gql::response::Response::Immediate(kv) => {
-                                    result.insert(kv.0.to_string(), kv.1);
-                                }
-                                ::tokio_gql::response::Response::Async(fut) => {
-                                    async_fields.push(fut)
-                                }
-                            }
+                            // Aliased fields end up under their alias.
+                            let (response_key, non_null) = match &field {
+                                #(#entry_arms)*
+                                #variant_name::__Typename { respond } => (respond.response_key().to_string(), None),
+                            };
+                            let response = match field {
+                                #variant_name::__Typename { respond } => respond.with(#name),
+                                field => resolver(field, &data),
+                            };
+
+                            object.field(response_key, non_null, response);
                         }
 
-                        Box::new(
-                            ::futures::future::join_all(async_fields)
-                                .map(move |r| (result, r))
-                                .and_then(move |(mut result, resolved)| {
-                                    result.extend(resolved.into_iter().map(|(k, v)| (k.to_string(), v)));
-                                    Ok(::serde_json::Value::Object(result))
-                                })
-                        )
-                    })
+                        object.resolve()
+                    }))
                 }
             }
 
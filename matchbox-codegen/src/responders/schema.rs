@@ -51,38 +51,24 @@ impl ImplResponder for schema::SchemaDefinition {
         quote! {
             #[derive(Debug, PartialEq)]
             pub struct #responder_name;
+            trivial_default_impl!(#responder_name, #responder_name);
 
             impl #responder_name {
                 pub fn to<Resolver>(
                     selection: Vec<Operation>,
                     resolver: Resolver
-                ) -> impl ::futures::future::Future<Item = ::serde_json::Value, Error = ::tokio_gql::errors::ResolverError>
+                ) -> impl ::futures::future::Future<Item = ::tokio_gql::response::Resolved, Error = ::tokio_gql::errors::ResolverError>
                 where
                     Resolver: Fn(Operation) -> ::tokio_gql::response::Response,
                 {
-                    use ::futures::prelude::Future;
-
-                    let mut result = ::serde_json::Map::with_capacity(selection.len());
-
-                    let mut async_fields: Vec<_> = Vec::new();
+                    let mut object = ::tokio_gql::response::ObjectResponse::new();
 
+                    // The operations respond with their data objects, whose keys are already the response keys (aliases included) of the root fields.
                     for field in selection.into_iter() {
-                        let response = resolver(field);
-                        match response {
-                            ::tokio_gql::response::Response::Immediate(kv) => {
-                                result.insert(kv.0.to_string(), kv.1);
-                            }
-                            ::tokio_gql::response::Response::Async(fut) => {
-                                async_fields.push(fut)
-                            }
-                        }
+                        object.fragment(resolver(field));
                     }
 
-                    ::futures::future::join_all(async_fields).and_then(move |resolved| {
-                        result.extend(resolved.into_iter().map(|(k, v)| (k.to_string(), v)));
-                        Ok(::serde_json::Value::Object(result))
-                    })
-
+                    object.resolve()
                 }
             }
         }
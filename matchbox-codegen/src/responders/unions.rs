@@ -5,16 +5,72 @@ use proc_macro2::{Span, Term};
 use quote;
 
 impl ImplResponder for schema::UnionType {
-    fn impl_responder(&self, context: &DeriveContext) -> quote::Tokens {
-        let responder_name = Term::new(
-            &::shared::schema_name_to_responder_name(&self.name),
-            Span::call_site(),
-        );
+    fn impl_responder(&self, _context: &DeriveContext) -> quote::Tokens {
+        abstract_responder_impl(&self.name, &[], self.types.iter())
+    }
+}
+
+/// The responder for an interface or union selection. Since the selection can contain fragments on any of the possible types, the concrete type of the value has to be passed in: it answers `__typename`, and fragments on other types are skipped.
+///
+/// The resolver responds to fields with their value, and to fragments with an object whose fields are merged into the response. As for objects, failing fields are null and their errors are reported.
+pub fn abstract_responder_impl<'a>(
+    name: &str,
+    fields: &[schema::Field],
+    possible_types: impl Iterator<Item = &'a String>,
+) -> quote::Tokens {
+    let responder_name = Term::new(
+        &::shared::schema_name_to_responder_name(name),
+        Span::call_site(),
+    );
+    let name_term = Term::new(name, Span::call_site());
+    let possible_types: Vec<&String> = possible_types.collect();
+    let skipped_fragments = possible_types.iter().map(|ty| {
+        let variant = Term::new(&format!("On{}", ty), Span::call_site());
+        quote!(#name_term::#variant(_) if typename != #ty => continue,)
+    });
+    let entry_arms = super::fields::response_entry_arms(name, fields);
+
+    quote! {
+        #[derive(Debug, PartialEq)]
+        pub struct #responder_name;
+        trivial_default_impl!(#responder_name, #responder_name);
+
+        #[allow(deprecated)]
+        impl #responder_name {
+            pub fn to<Resolver>(
+                &self,
+                typename: &str,
+                selection: Vec<#name_term>,
+                resolver: Resolver,
+            ) -> Box<::futures::Future<Item = ::tokio_gql::response::Resolved, Error = ::tokio_gql::errors::ResolverError>>
+            where
+                Resolver: Fn(#name_term) -> ::tokio_gql::response::Response,
+            {
+                let mut object = ::tokio_gql::response::ObjectResponse::new();
+
+                for field in selection.into_iter() {
+                    let (response_key, non_null) = match &field {
+                        #(#entry_arms)*
+                        #name_term::__Typename { respond } => (respond.response_key().to_string(), None),
+                        _ => {
+                            let response = match field {
+                                #(#skipped_fragments)*
+                                field => resolver(field),
+                            };
+                            object.fragment(response);
+                            continue;
+                        }
+                    };
+                    let response = match field {
+                        #name_term::__Typename { respond } => respond.with(typename),
+                        field => resolver(field),
+                    };
+
+                    object.field(response_key, non_null, response);
+                }
 
-        quote! {
-            #[derive(Debug, PartialEq)]
-            pub struct #responder_name;
-            trivial_default_impl!(#responder_name, #responder_name);
+                object.resolve()
+            }
         }
     }
 }
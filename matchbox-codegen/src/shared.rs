@@ -14,19 +14,23 @@ pub fn extract_inner_name(ty: &graphql_parser::query::Type) -> &str {
     }
 }
 
-pub fn gql_type_to_json_type(gql_type: &graphql_parser::query::Type) -> quote::Tokens {
-    gql_type_to_json_type_inner(gql_type, false)
+pub fn gql_type_to_json_type(
+    gql_type: &graphql_parser::query::Type,
+    context: &DeriveContext,
+) -> quote::Tokens {
+    gql_type_to_json_type_inner(gql_type, context, false)
 }
 
 fn gql_type_to_json_type_inner(
     gql_type: &graphql_parser::query::Type,
+    context: &DeriveContext,
     non_null: bool,
 ) -> quote::Tokens {
     use graphql_parser::query::Type::*;
 
     match gql_type {
         NamedType(name) => {
-            let inner_name = Term::new(correspondant_type(name.as_str()), Span::call_site());
+            let inner_name = correspondant_type(name.as_str(), context);
             if non_null {
                 quote!(#inner_name)
             } else {
@@ -34,7 +38,7 @@ fn gql_type_to_json_type_inner(
             }
         }
         ListType(inner) => {
-            let inner_converted = gql_type_to_json_type_inner(&inner, false);
+            let inner_converted = gql_type_to_json_type_inner(&inner, context, false);
             if non_null {
                 quote!(Vec<#inner_converted>)
             } else {
@@ -42,22 +46,32 @@ fn gql_type_to_json_type_inner(
             }
         }
         NonNullType(inner) => {
-            let inner_converted = gql_type_to_json_type_inner(&inner, true);
+            let inner_converted = gql_type_to_json_type_inner(&inner, context, true);
             quote!(#inner_converted)
         }
     }
 }
 
-/// Correspondance function between a GraphQL scalar type name(Int, String...) and rust types
-pub fn correspondant_type(gql_type: &str) -> &str {
-    match gql_type {
+/// Correspondance function between a GraphQL scalar type name(Int, String...) and rust types. Custom scalars map to the type given with the `scalar` derive attribute, or to the type of the same name.
+pub fn correspondant_type(gql_type: &str, context: &DeriveContext) -> quote::Tokens {
+    let name = match gql_type {
         "Int" => "i32",
         "String" => "String",
         "Float" => "f64",
         "Boolean" => "bool",
         "ID" => "String",
-        other => other,
-    }
+        other => match context.scalar_mappings.get(other) {
+            Some(rust_type) => {
+                let rust_type: ::syn::Type = ::syn::parse_str(rust_type).unwrap_or_else(|_| {
+                    panic!("invalid Rust type {:?} for scalar {}", rust_type, other)
+                });
+                return quote!(#rust_type);
+            }
+            None => other,
+        },
+    };
+    let name = Term::new(name, Span::call_site());
+    quote!(#name)
 }
 
 /// Will return true for nullable and non-nullable list types
@@ -95,6 +109,30 @@ pub fn value_variant_for_type(
     quote!(::tokio_gql::graphql_parser::schema::Value::#variant)
 }
 
+/// The pattern matching the literal values of that type. Custom scalars can be represented by any literal, so only variables are left out.
+pub fn value_pattern_for_type(
+    value_type: &graphql_parser::schema::Type,
+    context: &DeriveContext,
+) -> quote::Tokens {
+    let value = quote!(::tokio_gql::graphql_parser::schema::Value);
+
+    if !is_list_type(value_type) && context.is_custom_scalar(extract_inner_name(value_type)) {
+        quote! {
+            #value::Int(_)
+                | #value::Float(_)
+                | #value::String(_)
+                | #value::Boolean(_)
+                | #value::Null
+                | #value::Enum(_)
+                | #value::List(_)
+                | #value::Object(_)
+        }
+    } else {
+        let variant = value_variant_for_type(value_type, context);
+        quote!(#variant(_))
+    }
+}
+
 pub fn type_is_optional(value_type: &graphql_parser::schema::Type) -> bool {
     if let graphql_parser::schema::Type::NonNullType(_) = value_type {
         false
@@ -118,6 +156,46 @@ macro_rules! assert_expands_to {
     };
 }
 
+/// The reason given by the `@deprecated` directive, if the field or enum value is deprecated. The `reason` argument is optional, see https://facebook.github.io/graphql/June2018/#sec--deprecated
+pub fn deprecation_reason(directives: &[graphql_parser::schema::Directive]) -> Option<String> {
+    use graphql_parser::schema::Value;
+
+    directives
+        .iter()
+        .find(|directive| directive.name == "deprecated")
+        .map(|directive| {
+            directive
+                .arguments
+                .iter()
+                .find(|(name, _)| name == "reason")
+                .and_then(|(_, value)| match value {
+                    Value::String(reason) => Some(reason.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_else(|| "No longer supported".to_string())
+        })
+}
+
+/// A `#[deprecated]` attribute for the variants generated from deprecated fields and enum values, so resolvers using them get a warning.
+pub fn deprecated_attr(directives: &[graphql_parser::schema::Directive]) -> quote::Tokens {
+    match deprecation_reason(directives) {
+        Some(reason) => quote!(#[deprecated(note = #reason)]),
+        None => quote!(),
+    }
+}
+
+/// The variant for the `__typename` meta-field, shared by all selection enums. Field variants are camel-cased without underscores, so a schema field named `typename` cannot collide with it.
+pub fn typename_variant() -> quote::Tokens {
+    quote!(__Typename {
+        respond: ::tokio_gql::response::TypenameResponder,
+    })
+}
+
+/// Builds the `__typename` variant of a selection enum from the `field` in scope.
+pub fn typename_constructor(selection_name: &Term) -> quote::Tokens {
+    quote!(#selection_name::__Typename { respond: ::tokio_gql::response::TypenameResponder { alias: field.alias.clone() } })
+}
+
 pub fn query_value_to_tokens(value: &::graphql_parser::query::Value) -> quote::Tokens {
     use graphql_parser::query::Value;
 
@@ -198,8 +276,7 @@ fn graphql_type_to_response_type_inner(
         }
         Type::NamedType(ty) => {
             if context.is_scalar(ty) {
-                let ty = Term::new(correspondant_type(ty), Span::call_site());
-                quote!(#ty)
+                correspondant_type(ty, context)
             } else if context.is_enum(ty) {
                 let ty = Term::new(&ty.to_camel_case(), Span::call_site());
                 quote!(#ty)
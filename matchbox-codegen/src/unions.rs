@@ -10,9 +10,11 @@ pub fn gql_union_to_rs(union_type: &UnionType, _context: &DeriveContext) -> quot
         let selection_type = Term::new(ty.as_str(), Span::call_site());
         quote!(#ident(Vec<#selection_type>))
     });
+    let typename_variant = ::shared::typename_variant();
     quote! {
         #[derive(Debug, PartialEq)]
         pub enum #name {
+            #typename_variant,
             #(#united_types),*
         }
     }
@@ -30,6 +32,7 @@ mod tests {
             "## => {
                 #[derive(Debug, PartialEq)]
                 pub enum SearchResult {
+                    __Typename { respond: ::tokio_gql::response::TypenameResponder, },
                     OnHuman(Vec<Human>),
                     OnDroid(Vec<Droid>),
                     OnStarship(Vec<Starship>)
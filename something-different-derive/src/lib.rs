@@ -8,6 +8,7 @@ extern crate quote;
 extern crate syn;
 
 use proc_macro2::{Span, Term};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 
@@ -32,7 +33,9 @@ fn impl_something_different(ast: &syn::DeriveInput) -> quote::Tokens {
     file.read_to_string(&mut the_schema_string)
         .expect("Could not read schema.");
 
-    matchbox_codegen::expand_schema(&the_schema_string)
+    let scalars = extract_scalars(&ast.attrs);
+
+    matchbox_codegen::expand_schema_with_scalars(&the_schema_string, &scalars)
 }
 
 fn extract_path(attributes: &[syn::Attribute]) -> Option<String> {
@@ -58,3 +61,31 @@ fn extract_path(attributes: &[syn::Attribute]) -> Option<String> {
     }
     None
 }
+
+/// The Rust types custom scalars map to, from `scalar(DateTime = "chrono::DateTime<Utc>")` items.
+fn extract_scalars(attributes: &[syn::Attribute]) -> HashMap<String, String> {
+    let scalar_ident = Term::new("scalar", Span::call_site());
+    let mut scalars = HashMap::new();
+    for attr in attributes.iter() {
+        if let syn::Meta::List(items) = &attr.interpret_meta().expect("Attribute is well formatted")
+        {
+            for item in items.nested.iter() {
+                if let syn::NestedMeta::Meta(syn::Meta::List(list)) = item {
+                    if list.ident != scalar_ident.to_string() {
+                        continue;
+                    }
+                    for mapping in list.nested.iter() {
+                        if let syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) = mapping {
+                            if let syn::Lit::Str(lit) = &name_value.lit {
+                                scalars.insert(name_value.ident.to_string(), lit.value());
+                                continue;
+                            }
+                        }
+                        panic!("scalar mappings should look like scalar(DateTime = \"chrono::DateTime<Utc>\")");
+                    }
+                }
+            }
+        }
+    }
+    scalars
+}
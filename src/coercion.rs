@@ -1,10 +1,177 @@
 ///! This module contains the traits that are auto-implemented on the derived tree to extract it from a parsed request.
+use errors::{PathSegment, ResolverError};
 use graphql_parser::query::*;
+use graphql_parser::schema;
 use graphql_parser::schema::Value;
-use query_validation::ValidationContext;
+use query_validation::{find_field, query_value_to_json, select_operation, ValidationContext};
+use response::Resolved;
+use serde_json as json;
+use std::collections::VecDeque;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
-pub struct CoercionError;
+/// A part of the query that could not be coerced into the generated types, like an argument of the wrong type.
+///
+/// The generated code records the argument and the path to the field it belongs to, from the root of the operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoercionError {
+    reason: String,
+    path: VecDeque<String>,
+    argument: Option<String>,
+    expected_type: Option<String>,
+    value: Option<json::Value>,
+}
+
+impl CoercionError {
+    pub fn new(reason: impl Into<String>) -> CoercionError {
+        CoercionError {
+            reason: reason.into(),
+            path: VecDeque::new(),
+            argument: None,
+            expected_type: None,
+            value: None,
+        }
+    }
+
+    /// A value that is not of the expected GraphQL type, like `String!` or `[Episode]`.
+    pub fn invalid_value(expected_type: impl Into<String>, value: &Value) -> CoercionError {
+        CoercionError::expected(
+            expected_type.into(),
+            Some((value.to_string(), literal_to_json(value))),
+        )
+    }
+
+    /// A value of the expected GraphQL type is missing.
+    pub fn missing_value(expected_type: impl Into<String>) -> CoercionError {
+        CoercionError::expected(expected_type.into(), None)
+    }
+
+    /// An argument of `field` that is missing or could not be coerced. A variable is reported with the value it was given.
+    pub fn invalid_argument(
+        field: &Field,
+        argument: &str,
+        expected_type: &str,
+        context: &ValidationContext,
+    ) -> CoercionError {
+        let value = field
+            .arguments
+            .iter()
+            .find(|(name, _)| name == argument)
+            .and_then(|(_, value)| match value {
+                Value::Variable(name) => context
+                    .variables
+                    .get(name)
+                    .map(|value| (value.to_string(), value.clone())),
+                value => Some((value.to_string(), literal_to_json(value))),
+            });
+
+        CoercionError::expected(expected_type.to_string(), value).in_argument(field, argument)
+    }
+
+    /// A field of an input object that is missing or could not be coerced.
+    pub fn invalid_input_field(
+        input_type: &str,
+        input_field: &str,
+        expected_type: &str,
+        value: Option<&Value>,
+    ) -> CoercionError {
+        CoercionError {
+            reason: format!(
+                "expected {} for field {} of {}, found {}",
+                expected_type,
+                input_field,
+                input_type,
+                value
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| "no value".to_string())
+            ),
+            path: VecDeque::new(),
+            argument: None,
+            expected_type: Some(expected_type.to_string()),
+            value: value.map(literal_to_json),
+        }
+    }
+
+    /// `value` is the received value as written in the query, in GraphQL syntax for literals and JSON for variables, and as JSON.
+    fn expected(expected_type: String, value: Option<(String, json::Value)>) -> CoercionError {
+        let (found, value) = match value {
+            Some((found, value)) => (found, Some(value)),
+            None => ("no value".to_string(), None),
+        };
+
+        CoercionError {
+            reason: format!("expected {}, found {}", expected_type, found),
+            path: VecDeque::new(),
+            argument: None,
+            expected_type: Some(expected_type),
+            value,
+        }
+    }
+
+    /// Records that the error happened in an argument of `field`. The argument closest to the error is kept.
+    pub fn in_argument(mut self, field: &Field, argument: &str) -> CoercionError {
+        if self.argument.is_none() {
+            self.argument = Some(argument.to_string());
+        }
+        self.in_field(field)
+    }
+
+    /// Prepends the response key of `field` to the path. This is meant to be called as the error travels up the selection sets.
+    pub fn in_field(mut self, field: &Field) -> CoercionError {
+        let response_key = field.alias.as_ref().unwrap_or(&field.name);
+        self.path.push_front(response_key.to_string());
+        self
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// The response keys of the fields leading to the error.
+    pub fn path(&self) -> &VecDeque<String> {
+        &self.path
+    }
+
+    pub fn argument(&self) -> Option<&str> {
+        self.argument.as_ref().map(|argument| argument.as_str())
+    }
+
+    pub fn expected_type(&self) -> Option<&str> {
+        self.expected_type.as_ref().map(|ty| ty.as_str())
+    }
+
+    /// The value that was received, as JSON. Enum literals are represented by their name, like in variables.
+    pub fn value(&self) -> Option<&json::Value> {
+        self.value.as_ref()
+    }
+}
+
+/// Literals are converted like default values. Variables cannot be converted without their value, so they are kept in GraphQL syntax.
+fn literal_to_json(value: &Value) -> json::Value {
+    query_value_to_json(value).unwrap_or_else(|_| json::Value::String(value.to_string()))
+}
+
+impl fmt::Display for CoercionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path: Vec<&str> = self.path.iter().map(|key| key.as_str()).collect();
+
+        match self.argument {
+            Some(ref argument) => write!(
+                f,
+                "Invalid value for argument {} of {}: {}",
+                argument,
+                path.join("."),
+                self.reason
+            ),
+            None if !path.is_empty() => write!(
+                f,
+                "Invalid selection in {}: {}",
+                path.join("."),
+                self.reason
+            ),
+            None => write!(f, "Invalid query: {}", self.reason),
+        }
+    }
+}
 
 /// This should be implemented by the schema. It coerces a Schema struct from a query root, recursively coercing fields.
 pub trait CoerceQueryDocument: Sized {
@@ -19,21 +186,640 @@ pub trait CoerceSelection: Sized {
     ) -> Result<Vec<Self>, CoercionError>;
 }
 
-/// Coerces a response to match the query type.
-/// TODO: Figure out if we still need that. Probably not \o/
+/// A fragment in a selection set, either spread by name or inline. This is what the generated `CoerceSelection` impls use to handle both the same way.
+#[derive(Debug, PartialEq)]
+pub struct Fragment<'a> {
+    /// `None` for inline fragments without a type condition.
+    pub type_condition: Option<&'a str>,
+    pub selection_set: &'a SelectionSet,
+}
+
+impl<'a> Fragment<'a> {
+    /// Looks up the definition of a named fragment in the validation context.
+    pub fn from_spread(
+        spread: &FragmentSpread,
+        context: &'a ValidationContext,
+    ) -> Result<Fragment<'a>, CoercionError> {
+        let definition = context
+            .fragment_definition(&spread.fragment_name)
+            .ok_or_else(|| {
+                CoercionError::new(format!("unknown fragment {}", spread.fragment_name))
+            })?;
+        let TypeCondition::On(ref type_condition) = definition.type_condition;
+
+        Ok(Fragment {
+            type_condition: Some(type_condition),
+            selection_set: &definition.selection_set,
+        })
+    }
+
+    pub fn from_inline(fragment: &'a InlineFragment) -> Fragment<'a> {
+        Fragment {
+            type_condition: fragment
+                .type_condition
+                .as_ref()
+                .map(|TypeCondition::On(type_condition)| type_condition.as_str()),
+            selection_set: &fragment.selection_set,
+        }
+    }
+}
+
+/// Evaluates the `@skip` and `@include` directives on a selection. When both are present, the selection is included only if it is included and not skipped.
+pub fn is_included(selection: &Selection, context: &ValidationContext) -> Result<bool, CoercionError> {
+    let directives = match selection {
+        Selection::Field(field) => &field.directives,
+        Selection::FragmentSpread(spread) => &spread.directives,
+        Selection::InlineFragment(fragment) => &fragment.directives,
+    };
+
+    for directive in directives.iter() {
+        let excluded = match directive.name.as_str() {
+            "skip" => directive_condition(directive, context)?,
+            "include" => !directive_condition(directive, context)?,
+            _ => false,
+        };
+
+        if excluded {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// The value of the `if` argument of `@skip` and `@include`, which can be a variable.
+fn directive_condition(
+    directive: &Directive,
+    context: &ValidationContext,
+) -> Result<bool, CoercionError> {
+    let condition = directive
+        .arguments
+        .iter()
+        .find(|(name, _)| name == "if")
+        .map(|(_, value)| value)
+        .ok_or_else(|| CoercionError::missing_value("Boolean!"))?;
+
+    match condition {
+        Value::Boolean(condition) => Ok(*condition),
+        Value::Variable(name) => {
+            let value = context.variables.get(name);
+            value.and_then(|value| value.as_bool()).ok_or_else(|| {
+                CoercionError::new(format!(
+                    "expected Boolean! for the if argument of @{}, found {}",
+                    directive.name,
+                    value
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "no value".to_string())
+                ))
+            })
+        }
+        other => Err(CoercionError::invalid_value("Boolean!", other)),
+    }
+}
+
+/// How `CoerceResponse` treats the fields a resolver set but the query did not select.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pruning {
+    /// Unrequested fields are dropped from the response.
+    Lenient,
+    /// Unrequested fields are reported as errors. `__typename` is always allowed, since resolvers need it to tell the concrete type of interfaces and unions.
+    Strict,
+}
+
+impl Default for Pruning {
+    fn default() -> Pruning {
+        Pruning::Lenient
+    }
+}
+
+/// Coerces a resolver response to the shape of the query: unrequested fields are pruned, missing nullable fields are set to null and missing non-null fields are reported as errors.
+///
+/// Like errors raised by resolvers, a null in a non-null field makes its nearest nullable parent null.
+///
+/// This is implemented by the schema document the query was validated against.
 pub trait CoerceResponse {
-    fn coerce(query: &Document, response: ::serde_json::Value) -> ::serde_json::Value;
+    fn coerce_response(
+        &self,
+        query: &Document,
+        context: &ValidationContext,
+        response: Resolved,
+        pruning: Pruning,
+    ) -> Resolved;
+}
+
+impl CoerceResponse for schema::Document {
+    fn coerce_response(
+        &self,
+        query: &Document,
+        context: &ValidationContext,
+        response: Resolved,
+        pruning: Pruning,
+    ) -> Resolved {
+        let Resolved { value, mut errors } = response;
+
+        // The null already bubbled up to the root.
+        if value.is_null() && !errors.is_empty() {
+            return Resolved { value, errors };
+        }
+
+        let (root, selection_set) = match operation_shape(self, query, context) {
+            Ok(shape) => shape,
+            Err(error) => {
+                errors.push(error);
+                return Resolved {
+                    value: json::Value::Null,
+                    errors,
+                };
+            }
+        };
+
+        let mut shape = ResponseShape {
+            schema: self,
+            query_root: schema_definition(self)
+                .and_then(|definition| definition.query.as_ref())
+                .map(|name| name.as_str()),
+            context,
+            pruning,
+            path: Vec::new(),
+            errors,
+        };
+        let value = shape.coerce_object(root, &[selection_set], value);
+
+        Resolved {
+            value,
+            errors: shape.errors,
+        }
+    }
+}
+
+/// The name of the root type of the operation and its selection set.
+fn operation_shape<'a, 'b>(
+    schema: &'a schema::Document,
+    query: &'b Document,
+    context: &ValidationContext,
+) -> Result<(&'a str, &'b SelectionSet), ResolverError> {
+    let operation = select_operation(query, context.operation_name())
+        .map_err(|err| ResolverError::new(err.to_string()))?;
+
+    operation_root(schema, operation)
+        .ok_or_else(|| ResolverError::new("The schema does not define this operation"))
+}
+
+fn schema_definition(schema: &schema::Document) -> Option<&schema::SchemaDefinition> {
+    schema
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            schema::Definition::SchemaDefinition(definition) => Some(definition),
+            _ => None,
+        })
+        .next()
+}
+
+/// The root type name and the selection set of an operation.
+fn operation_root<'a, 'b>(
+    schema: &'a schema::Document,
+    operation: &'b OperationDefinition,
+) -> Option<(&'a str, &'b SelectionSet)> {
+    let schema_definition = schema_definition(schema)?;
+
+    let (root, selection_set) = match operation {
+        OperationDefinition::Query(query) => (&schema_definition.query, &query.selection_set),
+        OperationDefinition::Mutation(mutation) => {
+            (&schema_definition.mutation, &mutation.selection_set)
+        }
+        OperationDefinition::Subscription(subscription) => {
+            (&schema_definition.subscription, &subscription.selection_set)
+        }
+        OperationDefinition::SelectionSet(selection_set) => {
+            (&schema_definition.query, selection_set)
+        }
+    };
+
+    root.as_ref().map(|root| (root.as_str(), selection_set))
+}
+
+/// A field of the response, with the selection sets of all the fields that share its response key.
+struct CollectedField<'a> {
+    response_key: &'a str,
+    name: &'a str,
+    /// The type the field is selected on, which is the type condition for fields in fragments.
+    parent_type: &'a str,
+    selection_sets: Vec<&'a SelectionSet>,
+    /// Whether the field comes from a fragment that may not apply, because the concrete type of the object is unknown. Those fields are kept if the resolver set them, but not required.
+    conditional: bool,
+}
+
+/// Walks the response along the query, accumulating errors with their path.
+struct ResponseShape<'a> {
+    schema: &'a schema::Document,
+    /// The name of the query root type, which has the introspection meta-fields.
+    query_root: Option<&'a str>,
+    context: &'a ValidationContext,
+    pruning: Pruning,
+    path: Vec<PathSegment>,
+    errors: Vec<ResolverError>,
+}
+
+impl<'a> ResponseShape<'a> {
+    fn error(&mut self, message: String) {
+        self.errors
+            .push(ResolverError::new(message).with_path(self.path.clone()));
+    }
+
+    /// Reports a null in a non-null position, unless it is explained by an error that was already reported for the current path or one of its parents. Errors without a path explain no null.
+    fn null_error(&mut self, message: String) {
+        let already_reported = self.errors.iter().any(|error| {
+            let reported = error.path();
+
+            !reported.is_empty()
+                && reported.len() <= self.path.len()
+                && reported
+                    .iter()
+                    .zip(self.path.iter())
+                    .all(|(reported, current)| reported == current)
+        });
+
+        if !already_reported {
+            self.error(message);
+        }
+    }
+
+    fn type_definition(&self, name: &str) -> Option<&'a schema::TypeDefinition> {
+        self.schema
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                schema::Definition::TypeDefinition(definition) => Some(definition),
+                _ => None,
+            })
+            .find(|definition| match definition {
+                schema::TypeDefinition::Scalar(scalar) => scalar.name == name,
+                schema::TypeDefinition::Object(object) => object.name == name,
+                schema::TypeDefinition::Interface(interface) => interface.name == name,
+                schema::TypeDefinition::Union(union) => union.name == name,
+                schema::TypeDefinition::Enum(enum_type) => enum_type.name == name,
+                schema::TypeDefinition::InputObject(input) => input.name == name,
+            })
+            // Introspection results are shaped like any other field.
+            .or_else(|| ::introspection::introspection_type(name))
+    }
+
+    fn field_type(&self, parent_type: &str, field_name: &str) -> Option<&'a Type> {
+        let parent_type = self.type_definition(parent_type)?;
+
+        find_field(parent_type, field_name, self.query_root).map(|field| &field.field_type)
+    }
+
+    /// Whether a fragment with the given type condition applies to an object of type `object_type`.
+    fn fragment_applies(&self, type_condition: &str, object_type: &str) -> bool {
+        if type_condition == object_type {
+            return true;
+        }
+
+        match self.type_definition(type_condition) {
+            Some(schema::TypeDefinition::Interface(_)) => match self.type_definition(object_type) {
+                Some(schema::TypeDefinition::Object(object)) => object
+                    .implements_interfaces
+                    .iter()
+                    .any(|interface| interface == type_condition),
+                _ => false,
+            },
+            Some(schema::TypeDefinition::Union(union)) => {
+                union.types.iter().any(|member| member == object_type)
+            }
+            _ => false,
+        }
+    }
+
+    fn collect_fields(
+        &mut self,
+        parent_type: &'a str,
+        object_type: Option<&str>,
+        selection_set: &'a SelectionSet,
+        conditional: bool,
+        fields: &mut Vec<CollectedField<'a>>,
+    ) {
+        for selection in selection_set.items.iter() {
+            match is_included(selection, self.context) {
+                Ok(true) => (),
+                Ok(false) => continue,
+                Err(_) => {
+                    self.error("Invalid @skip or @include directive".to_string());
+                    continue;
+                }
+            }
+
+            let fragment = match selection {
+                Selection::Field(field) => {
+                    let response_key = field.alias.as_ref().unwrap_or(&field.name).as_str();
+
+                    match fields
+                        .iter_mut()
+                        .find(|collected| collected.response_key == response_key)
+                    {
+                        Some(collected) => {
+                            collected.selection_sets.push(&field.selection_set);
+                            collected.conditional &= conditional;
+                        }
+                        None => fields.push(CollectedField {
+                            response_key,
+                            name: &field.name,
+                            parent_type,
+                            selection_sets: vec![&field.selection_set],
+                            conditional,
+                        }),
+                    }
+                    continue;
+                }
+                Selection::FragmentSpread(spread) => {
+                    match Fragment::from_spread(spread, self.context) {
+                        Ok(fragment) => fragment,
+                        Err(_) => {
+                            self.error(format!("Unknown fragment {}", spread.fragment_name));
+                            continue;
+                        }
+                    }
+                }
+                Selection::InlineFragment(fragment) => Fragment::from_inline(fragment),
+            };
+
+            match (fragment.type_condition, object_type) {
+                (None, _) => self.collect_fields(
+                    parent_type,
+                    object_type,
+                    fragment.selection_set,
+                    conditional,
+                    fields,
+                ),
+                (Some(type_condition), _) if type_condition == parent_type => self.collect_fields(
+                    parent_type,
+                    object_type,
+                    fragment.selection_set,
+                    conditional,
+                    fields,
+                ),
+                (Some(type_condition), Some(object_type)) => {
+                    if self.fragment_applies(type_condition, object_type) {
+                        self.collect_fields(
+                            type_condition,
+                            Some(object_type),
+                            fragment.selection_set,
+                            conditional,
+                            fields,
+                        )
+                    }
+                }
+                (Some(type_condition), None) => {
+                    self.collect_fields(type_condition, None, fragment.selection_set, true, fields)
+                }
+            }
+        }
+    }
+
+    fn coerce_object(
+        &mut self,
+        type_name: &'a str,
+        selection_sets: &[&'a SelectionSet],
+        value: json::Value,
+    ) -> json::Value {
+        let mut object = match value {
+            json::Value::Object(object) => object,
+            other => {
+                self.error(format!(
+                    "Expected an object of type {}, found {}",
+                    type_name, other
+                ));
+                return json::Value::Null;
+            }
+        };
+
+        // The concrete type of interfaces and unions is only known if the resolver set `__typename`.
+        let object_type = match self.type_definition(type_name) {
+            Some(schema::TypeDefinition::Object(_)) => Some(type_name.to_string()),
+            _ => object
+                .get("__typename")
+                .and_then(|typename| typename.as_str())
+                .map(|typename| typename.to_string()),
+        };
+
+        let mut fields = Vec::new();
+        for selection_set in selection_sets {
+            self.collect_fields(
+                type_name,
+                object_type.as_ref().map(|name| name.as_str()),
+                selection_set,
+                false,
+                &mut fields,
+            );
+        }
+
+        let mut coerced = json::Map::new();
+        let mut is_null = false;
+
+        for field in fields {
+            let value = object.remove(field.response_key);
+            self.path.push(field.response_key.into());
+
+            let value = match (field.name, value) {
+                ("__typename", Some(value)) => Some(value),
+                ("__typename", None) => match object_type {
+                    Some(ref object_type) => Some(json::Value::String(object_type.clone())),
+                    None => {
+                        self.null_error(format!(
+                            "Missing value for non-null field {}.__typename",
+                            type_name
+                        ));
+                        is_null = true;
+                        None
+                    }
+                },
+                (_, None) if field.conditional => None,
+                (name, value) => match self.field_type(field.parent_type, name) {
+                    Some(field_type) => {
+                        let description = format!("{}.{}", field.parent_type, name);
+                        let value = match (field_type, value) {
+                            (Type::NonNullType(_), None) => {
+                                self.null_error(format!(
+                                    "Missing value for non-null field {}",
+                                    description
+                                ));
+                                json::Value::Null
+                            }
+                            (field_type, value) => self.coerce_value(
+                                field_type,
+                                &field.selection_sets,
+                                value.unwrap_or(json::Value::Null),
+                                &description,
+                            ),
+                        };
+
+                        // The null bubbles up to the nearest nullable parent.
+                        if value.is_null() && matches!(field_type, Type::NonNullType(_)) {
+                            is_null = true;
+                        }
+
+                        Some(value)
+                    }
+                    // Validation guarantees the field exists.
+                    None => None,
+                },
+            };
+
+            self.path.pop();
+
+            if let Some(value) = value {
+                coerced.insert(field.response_key.to_string(), value);
+            }
+        }
+
+        if self.pruning == Pruning::Strict {
+            for key in object.keys().filter(|key| *key != "__typename") {
+                self.path.push(key.as_str().into());
+                self.error(format!("Unexpected field {} on type {}", key, type_name));
+                self.path.pop();
+            }
+        }
+
+        if is_null {
+            json::Value::Null
+        } else {
+            json::Value::Object(coerced)
+        }
+    }
+
+    fn coerce_value(
+        &mut self,
+        field_type: &'a Type,
+        selection_sets: &[&'a SelectionSet],
+        value: json::Value,
+        description: &str,
+    ) -> json::Value {
+        match (field_type, value) {
+            (Type::NonNullType(_), json::Value::Null) => {
+                self.null_error(format!("Null value for non-null field {}", description));
+                json::Value::Null
+            }
+            (Type::NonNullType(inner), value) => {
+                self.coerce_value(inner, selection_sets, value, description)
+            }
+            (_, json::Value::Null) => json::Value::Null,
+            (Type::ListType(inner), json::Value::Array(items)) => {
+                let mut coerced = Vec::with_capacity(items.len());
+
+                let mut is_null = false;
+
+                for (index, item) in items.into_iter().enumerate() {
+                    self.path.push(index.into());
+                    let item = self.coerce_value(inner, selection_sets, item, description);
+                    is_null |= item.is_null() && matches!(**inner, Type::NonNullType(_));
+                    coerced.push(item);
+                    self.path.pop();
+                }
+
+                if is_null {
+                    json::Value::Null
+                } else {
+                    json::Value::Array(coerced)
+                }
+            }
+            (Type::ListType(_), other) => {
+                self.error(format!(
+                    "Expected a list for field {}, found {}",
+                    description, other
+                ));
+                json::Value::Null
+            }
+            (Type::NamedType(name), value) => match self.type_definition(name) {
+                Some(schema::TypeDefinition::Object(_))
+                | Some(schema::TypeDefinition::Interface(_))
+                | Some(schema::TypeDefinition::Union(_)) => {
+                    self.coerce_object(name, selection_sets, value)
+                }
+                _ => value,
+            },
+        }
+    }
 }
 
 pub trait CoerceScalar: Sized {
     fn coerce(value: &Value) -> Result<Self, CoercionError>;
+
+    /// Coerces the JSON value of a variable. By default the JSON value is converted to the equivalent GraphQL value and coerced like a literal.
+    fn coerce_json(value: &json::Value) -> Result<Self, CoercionError> {
+        Self::coerce(&json_to_query_value(value)?)
+    }
+}
+
+/// The Rust representation of a custom scalar, like `scalar DateTime`.
+///
+/// Custom scalars map to the Rust type of the same name, unless another type is given with the `scalar` derive attribute, for example `#[SomethingCompletelyDifferent(path = "schema.graphql", scalar(DateTime = "my_types::DateTime"))]`. That type has to implement `ScalarValue`, which the generated code uses to read it from arguments, input objects and variables, and to write it in responses.
+pub trait ScalarValue: Sized {
+    /// Parses a literal from the query.
+    fn from_query_value(value: &Value) -> Result<Self, CoercionError>;
+    /// Parses the JSON value of a variable.
+    fn from_json(value: &json::Value) -> Result<Self, CoercionError>;
+    /// The JSON representation of the scalar in responses.
+    fn to_json(&self) -> json::Value;
+}
+
+impl<T> CoerceScalar for T
+where
+    T: ScalarValue,
+{
+    fn coerce(value: &Value) -> Result<T, CoercionError> {
+        T::from_query_value(value)
+    }
+
+    fn coerce_json(value: &json::Value) -> Result<T, CoercionError> {
+        T::from_json(value)
+    }
+}
+
+/// Used by the generated input objects to deserialize custom scalar fields with `CoerceScalar::coerce_json`. The reason of a coercion error becomes the message of the deserialization error.
+pub fn deserialize_scalar<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: ::serde::Deserializer<'de>,
+    T: CoerceScalar,
+{
+    let value = <json::Value as ::serde::Deserialize>::deserialize(deserializer)?;
+    T::coerce_json(&value).map_err(|err| <D::Error as ::serde::de::Error>::custom(err.reason()))
+}
+
+fn json_to_query_value(value: &json::Value) -> Result<Value, CoercionError> {
+    let value = match value {
+        json::Value::Null => Value::Null,
+        json::Value::Bool(b) => Value::Boolean(*b),
+        json::Value::Number(n) => match n.as_i64() {
+            Some(n) if n >= i32::min_value() as i64 && n <= i32::max_value() as i64 => {
+                Value::Int((n as i32).into())
+            }
+            _ => Value::Float(n.as_f64().ok_or_else(|| {
+                CoercionError::new(format!("the number {} cannot be represented", n))
+            })?),
+        },
+        json::Value::String(s) => Value::String(s.to_string()),
+        json::Value::Array(items) => Value::List(
+            items
+                .iter()
+                .map(json_to_query_value)
+                .collect::<Result<_, _>>()?,
+        ),
+        json::Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.to_string(), json_to_query_value(v)?)))
+                .collect::<Result<_, CoercionError>>()?,
+        ),
+    };
+
+    Ok(value)
 }
 
 impl CoerceScalar for String {
     fn coerce(value: &Value) -> Result<String, CoercionError> {
         match value {
             Value::String(ref s) => Ok(s.to_string()),
-            _ => Err(CoercionError),
+            other => Err(CoercionError::invalid_value("String", other)),
         }
     }
 }
@@ -41,8 +827,14 @@ impl CoerceScalar for String {
 impl CoerceScalar for i32 {
     fn coerce(value: &Value) -> Result<i32, CoercionError> {
         match value {
-            Value::Int(i) => Ok(i.as_i64().unwrap() as i32),
-            _ => Err(CoercionError),
+            // GraphQL integers are 32 bits wide.
+            Value::Int(i) => match i.as_i64() {
+                Some(n) if n >= i32::min_value() as i64 && n <= i32::max_value() as i64 => {
+                    Ok(n as i32)
+                }
+                _ => Err(CoercionError::invalid_value("Int", value)),
+            },
+            other => Err(CoercionError::invalid_value("Int", other)),
         }
     }
 }
@@ -51,7 +843,7 @@ impl CoerceScalar for bool {
     fn coerce(value: &Value) -> Result<bool, CoercionError> {
         match value {
             Value::Boolean(b) => Ok(*b),
-            _ => Err(CoercionError),
+            other => Err(CoercionError::invalid_value("Boolean", other)),
         }
     }
 }
@@ -61,7 +853,17 @@ where
     T: CoerceScalar,
 {
     fn coerce(value: &Value) -> Result<Option<T>, CoercionError> {
-        Ok(T::coerce(value).ok())
+        match value {
+            Value::Null => Ok(None),
+            value => T::coerce(value).map(Some),
+        }
+    }
+
+    fn coerce_json(value: &json::Value) -> Result<Option<T>, CoercionError> {
+        match value {
+            json::Value::Null => Ok(None),
+            value => T::coerce_json(value).map(Some),
+        }
     }
 }
 
@@ -72,7 +874,313 @@ where
     fn coerce(value: &Value) -> Result<Vec<T>, CoercionError> {
         match value {
             Value::List(elems) => elems.iter().map(T::coerce).collect(),
-            _ => Err(CoercionError),
+            other => Err(CoercionError::new(format!(
+                "expected a list, found {}",
+                other
+            ))),
+        }
+    }
+
+    fn coerce_json(value: &json::Value) -> Result<Vec<T>, CoercionError> {
+        match value {
+            json::Value::Array(elems) => elems.iter().map(T::coerce_json).collect(),
+            other => Err(CoercionError::new(format!(
+                "expected a list, found {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_parser::{parse_query, parse_schema};
+    use query_validation::validate_query;
+
+    const SCHEMA: &str = r##"
+    interface Character {
+        name: String!
+    }
+
+    type Droid implements Character {
+        name: String!
+        primaryFunction: String
+    }
+
+    type Human implements Character {
+        name: String!
+        homePlanet: String
+    }
+
+    type Query {
+        hero: Character
+        droids: [Droid!]!
+        greeting: String!
+    }
+
+    schema {
+        query: Query
+    }
+    "##;
+
+    fn coerce_response(query: &str, response: impl Into<Resolved>, pruning: Pruning) -> Resolved {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let query = parse_query(query).unwrap();
+        let context = validate_query(&query, json::Map::new(), None, &schema).unwrap();
+        schema.coerce_response(&query, &context, response.into(), pruning)
+    }
+
+    #[test]
+    fn int_coercion_is_range_checked() {
+        assert_eq!(i32::coerce(&Value::Int(42.into())), Ok(42));
+        assert_eq!(
+            i32::coerce(&Value::Int(i32::min_value().into())),
+            Ok(i32::min_value())
+        );
+
+        let too_big = parse_query("{ a(n: 2147483648) }").unwrap();
+        let value = match too_big.definitions[0] {
+            Definition::Operation(OperationDefinition::SelectionSet(ref set)) => {
+                match set.items[0] {
+                    Selection::Field(ref field) => field.arguments[0].1.clone(),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            i32::coerce(&value).unwrap_err().reason(),
+            "expected Int, found 2147483648"
+        );
+    }
+
+    #[test]
+    fn unrequested_fields_are_pruned() {
+        assert_eq!(
+            coerce_response(
+                "query { greeting droids { name } }",
+                json!({
+                    "greeting": "Hello",
+                    "hero": { "name": "R2-D2" },
+                    "droids": [
+                        { "name": "R2-D2", "primaryFunction": "Astromech" },
+                        { "name": "C-3PO" },
+                    ],
+                }),
+                Pruning::Lenient
+            ),
+            Resolved::from(json!({
+                "greeting": "Hello",
+                "droids": [{ "name": "R2-D2" }, { "name": "C-3PO" }],
+            }))
+        );
+    }
+
+    #[test]
+    fn missing_nullable_fields_are_null() {
+        assert_eq!(
+            coerce_response(
+                "query { hero { name } droids { name primaryFunction } greeting }",
+                json!({ "droids": [{ "name": "R2-D2" }], "greeting": "Hello" }),
+                Pruning::Lenient
+            ),
+            Resolved::from(json!({
+                "hero": null,
+                "droids": [{ "name": "R2-D2", "primaryFunction": null }],
+                "greeting": "Hello",
+            }))
+        );
+    }
+
+    #[test]
+    fn missing_non_null_fields_are_reported() {
+        assert_eq!(
+            coerce_response(
+                "query { droids { name } greeting }",
+                json!({ "droids": [{ "name": "R2-D2" }, {}, null] }),
+                Pruning::Lenient
+            ),
+            Resolved {
+                value: json::Value::Null,
+                errors: vec![
+                    ResolverError::new("Missing value for non-null field Droid.name")
+                        .with_path(vec!["droids".into(), 1.into(), "name".into()]),
+                    ResolverError::new("Null value for non-null field Query.droids")
+                        .with_path(vec!["droids".into(), 2.into()]),
+                    ResolverError::new("Missing value for non-null field Query.greeting")
+                        .with_path(vec!["greeting".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn nulls_bubble_up_to_the_nearest_nullable_parent() {
+        assert_eq!(
+            coerce_response(
+                "query { hero { name } greeting }",
+                json!({ "hero": { "name": null }, "greeting": "Hello" }),
+                Pruning::Lenient
+            ),
+            Resolved {
+                value: json!({ "hero": null, "greeting": "Hello" }),
+                errors: vec![
+                    ResolverError::new("Null value for non-null field Character.name")
+                        .with_path(vec!["hero".into(), "name".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn resolver_errors_are_not_reported_twice() {
+        let error = ResolverError::new("No droids today").with_path(vec!["droids".into()]);
+
+        assert_eq!(
+            coerce_response(
+                "query { droids { name } greeting }",
+                Resolved {
+                    value: json!({ "droids": null, "greeting": "Hello" }),
+                    errors: vec![error],
+                },
+                Pruning::Lenient
+            ),
+            Resolved {
+                value: json::Value::Null,
+                errors: vec![
+                    ResolverError::new("No droids today").with_path(vec!["droids".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn resolver_errors_without_path_do_not_explain_nulls() {
+        assert_eq!(
+            coerce_response(
+                "query { droids { name } greeting }",
+                Resolved {
+                    value: json!({ "droids": null, "greeting": "Hello" }),
+                    errors: vec![ResolverError::new("Something went wrong")],
+                },
+                Pruning::Lenient
+            ),
+            Resolved {
+                value: json::Value::Null,
+                errors: vec![
+                    ResolverError::new("Something went wrong"),
+                    ResolverError::new("Null value for non-null field Query.droids")
+                        .with_path(vec!["droids".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn aliases_and_skipped_fields() {
+        assert_eq!(
+            coerce_response(
+                "query { welcome: greeting droids @skip(if: true) { name } }",
+                json!({ "welcome": "Hello", "greeting": "Hi", "droids": [] }),
+                Pruning::Lenient
+            ),
+            Resolved::from(json!({ "welcome": "Hello" }))
+        );
+    }
+
+    #[test]
+    fn strict_pruning_rejects_unrequested_fields() {
+        assert_eq!(
+            coerce_response(
+                "query { hero { name } }",
+                json!({
+                    "hero": { "__typename": "Human", "name": "Luke", "homePlanet": "Tatooine" },
+                    "greeting": "Hello",
+                }),
+                Pruning::Strict
+            ),
+            Resolved {
+                value: json!({ "hero": { "name": "Luke" } }),
+                errors: vec![
+                    ResolverError::new("Unexpected field homePlanet on type Character")
+                        .with_path(vec!["hero".into(), "homePlanet".into()]),
+                    ResolverError::new("Unexpected field greeting on type Query")
+                        .with_path(vec!["greeting".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn fragments_on_abstract_types() {
+        let query = r##"
+        query {
+            hero {
+                __typename
+                name
+                ... on Droid { primaryFunction }
+                ...HumanFields
+            }
         }
+
+        fragment HumanFields on Human {
+            homePlanet
+        }
+        "##;
+
+        assert_eq!(
+            coerce_response(
+                query,
+                json!({
+                    "hero": {
+                        "__typename": "Droid",
+                        "name": "R2-D2",
+                        "homePlanet": "Naboo",
+                    },
+                }),
+                Pruning::Lenient
+            ),
+            Resolved::from(json!({
+                "hero": { "__typename": "Droid", "name": "R2-D2", "primaryFunction": null },
+            }))
+        );
+
+        // Without `__typename`, the fields of fragments on concrete types are kept if they are set.
+        assert_eq!(
+            coerce_response(
+                "query { hero { name ... on Human { homePlanet } ... on Droid { primaryFunction } } }",
+                json!({ "hero": { "name": "Luke", "homePlanet": "Tatooine" } }),
+                Pruning::Lenient
+            ),
+            Resolved::from(json!({ "hero": { "name": "Luke", "homePlanet": "Tatooine" } }))
+        );
+
+        assert_eq!(
+            coerce_response(
+                "query { hero { __typename } }",
+                json!({ "hero": {} }),
+                Pruning::Lenient
+            ),
+            Resolved {
+                value: json!({ "hero": null }),
+                errors: vec![
+                    ResolverError::new("Missing value for non-null field Character.__typename")
+                        .with_path(vec!["hero".into(), "__typename".into()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn typename_is_filled_for_object_types() {
+        assert_eq!(
+            coerce_response(
+                "query { __typename droids { __typename } }",
+                json!({ "droids": [{}] }),
+                Pruning::Strict
+            ),
+            Resolved::from(json!({ "__typename": "Query", "droids": [{ "__typename": "Droid" }] }))
+        );
     }
 }
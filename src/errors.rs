@@ -1,51 +1,345 @@
 use coercion::CoercionError;
+use graphql_parser;
 use graphql_parser::query::ParseError;
+use query_validation::QueryValidationError;
 use serde_json as json;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::convert::From;
+use std::fmt;
 
-#[derive(Debug)]
+/// An error raised by a resolver, at a given path in the response.
+#[derive(Debug, PartialEq)]
 pub struct ResolverError {
     message: String,
-    path: VecDeque<String>,
+    path: VecDeque<PathSegment>,
+    extensions: HashMap<String, json::Value>,
+}
+
+impl ResolverError {
+    pub fn new(message: impl Into<String>) -> ResolverError {
+        ResolverError {
+            message: message.into(),
+            path: VecDeque::new(),
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// Sets the full path of the error, from the root of the response.
+    pub fn with_path(mut self, path: impl IntoIterator<Item = PathSegment>) -> ResolverError {
+        self.path = path.into_iter().collect();
+        self
+    }
+
+    /// Adds an entry to the `extensions` map of the error, for example an error code.
+    pub fn with_extension(mut self, key: impl Into<String>, value: json::Value) -> ResolverError {
+        self.extensions.insert(key.into(), value);
+        self
+    }
+
+    /// Prepends a segment to the path. This is meant to be called as the error travels up the response tree.
+    pub fn prepend_path(&mut self, segment: impl Into<PathSegment>) {
+        self.path.push_front(segment.into());
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn path(&self) -> &VecDeque<PathSegment> {
+        &self.path
+    }
+}
+
+impl fmt::Display for ResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
 }
 
 #[derive(Debug, Fail)]
 pub enum GqlError {
     #[fail(display = "Invalid request")]
     InvalidRequest,
-    #[fail(display = "Invalid query")]
-    InvalidQuery,
-    #[fail(display = "Resolver error")]
+    #[fail(display = "{}", _0)]
+    InvalidQuery(ParseError),
+    #[fail(display = "{}", _0)]
+    ValidationError(QueryValidationError),
+    #[fail(display = "{}", _0)]
     ResolverError(ResolverError),
-    #[fail(display = "Invalid error")]
+    #[fail(display = "{}", _0)]
+    CoercionError(CoercionError),
+    #[fail(display = "Internal server error")]
     InternalError,
 }
 
-struct Pos {
-    line: u16,
-    column: u16,
+/// A line and column in the query document, both starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<graphql_parser::Pos> for Pos {
+    fn from(pos: graphql_parser::Pos) -> Self {
+        Pos {
+            line: pos.line,
+            column: pos.column,
+        }
+    }
+}
+
+/// A segment of the path to the response field an error is associated with: either a field name (or alias) or an index in a list.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+impl<'a> From<&'a str> for PathSegment {
+    fn from(field: &'a str) -> Self {
+        PathSegment::Field(field.to_string())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(field: String) -> Self {
+        PathSegment::Field(field)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
 }
 
 /// The errors as returned in the response.
 ///
 /// This is part of the [official spec](https://github.com/facebook/graphql/blob/master/spec/Section%207%20--%20Response.md).
-struct ResponseError {
-    message: String,
-    locations: Option<Vec<Pos>>,
-    path: Option<Vec<String>>,
-    extensions: Option<HashMap<String, json::Value>>,
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ResponseError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locations: Option<Vec<Pos>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<PathSegment>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<HashMap<String, json::Value>>,
+}
+
+impl ResponseError {
+    pub fn new(message: impl Into<String>) -> ResponseError {
+        ResponseError {
+            message: message.into(),
+            locations: None,
+            path: None,
+            extensions: None,
+        }
+    }
+
+    pub fn with_location(mut self, position: impl Into<Pos>) -> ResponseError {
+        self.locations
+            .get_or_insert_with(Vec::new)
+            .push(position.into());
+        self
+    }
+
+    pub fn with_extension(mut self, key: impl Into<String>, value: json::Value) -> ResponseError {
+        self.extensions
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value);
+        self
+    }
+}
+
+impl From<QueryValidationError> for ResponseError {
+    fn from(err: QueryValidationError) -> Self {
+        let response_error = ResponseError::new(err.to_string());
+
+        match err.position() {
+            Some(position) => response_error.with_location(position),
+            None => response_error,
+        }
+    }
+}
+
+/// The details of the error are in the extensions: `argument`, `expectedType` and `value`, when they are known.
+impl From<CoercionError> for ResponseError {
+    fn from(err: CoercionError) -> Self {
+        let mut response_error = ResponseError::new(err.to_string());
+
+        if !err.path().is_empty() {
+            response_error.path = Some(err.path().iter().map(|key| key.as_str().into()).collect());
+        }
+
+        let details = vec![
+            ("argument", err.argument().map(|argument| json!(argument))),
+            ("expectedType", err.expected_type().map(|ty| json!(ty))),
+            ("value", err.value().cloned()),
+        ];
+
+        for (key, detail) in details {
+            if let Some(detail) = detail {
+                response_error = response_error.with_extension(key, detail);
+            }
+        }
+
+        response_error
+    }
+}
+
+impl From<ResolverError> for ResponseError {
+    fn from(err: ResolverError) -> Self {
+        let ResolverError {
+            message,
+            path,
+            extensions,
+        } = err;
+
+        ResponseError {
+            message,
+            locations: None,
+            path: if path.is_empty() {
+                None
+            } else {
+                Some(path.into_iter().collect())
+            },
+            extensions: if extensions.is_empty() {
+                None
+            } else {
+                Some(extensions)
+            },
+        }
+    }
+}
+
+impl From<GqlError> for ResponseError {
+    fn from(err: GqlError) -> Self {
+        match err {
+            GqlError::ValidationError(err) => err.into(),
+            GqlError::ResolverError(err) => err.into(),
+            GqlError::CoercionError(err) => err.into(),
+            other => ResponseError::new(other.to_string()),
+        }
+    }
 }
 
 impl From<CoercionError> for GqlError {
-    fn from(_err: CoercionError) -> Self {
-        GqlError::InternalError
+    fn from(err: CoercionError) -> Self {
+        GqlError::CoercionError(err)
     }
 }
 
 impl From<ParseError> for GqlError {
-    fn from(_err: ParseError) -> Self {
-        GqlError::InvalidQuery
+    fn from(err: ParseError) -> Self {
+        GqlError::InvalidQuery(err)
+    }
+}
+
+impl From<QueryValidationError> for GqlError {
+    fn from(err: QueryValidationError) -> Self {
+        GqlError::ValidationError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use query_validation::ValidationContext;
+
+    #[test]
+    fn response_error_serialization() {
+        let error = ResponseError::new("Name for character with ID 1002 could not be fetched.")
+            .with_location(Pos { line: 6, column: 7 })
+            .with_extension("code", json!("CAN_NOT_FETCH_BY_ID"));
+        let error = ResponseError {
+            path: Some(vec!["hero".into(), "heroFriends".into(), 1.into(), "name".into()]),
+            ..error
+        };
+
+        assert_eq!(
+            json::to_value(&error).unwrap(),
+            json!({
+                "message": "Name for character with ID 1002 could not be fetched.",
+                "locations": [{ "line": 6, "column": 7 }],
+                "path": ["hero", "heroFriends", 1, "name"],
+                "extensions": { "code": "CAN_NOT_FETCH_BY_ID" },
+            })
+        );
+    }
+
+    #[test]
+    fn minimal_response_error_serialization() {
+        assert_eq!(
+            json::to_value(&ResponseError::new("Oops")).unwrap(),
+            json!({ "message": "Oops" })
+        );
+    }
+
+    #[test]
+    fn resolver_error_conversion() {
+        let mut error = ResolverError::new("No such droid")
+            .with_path(vec![PathSegment::Index(0), "name".into()])
+            .with_extension("code", json!(404));
+        error.prepend_path("droids");
+
+        assert_eq!(
+            json::to_value(&ResponseError::from(error)).unwrap(),
+            json!({
+                "message": "No such droid",
+                "path": ["droids", 0, "name"],
+                "extensions": { "code": 404 },
+            })
+        );
+    }
+
+    #[test]
+    fn coercion_error_conversion() {
+        use graphql_parser::query::{Definition, OperationDefinition, Selection};
+
+        let query =
+            graphql_parser::parse_query("{ hero { friends(first: \"ten\") { name } } }").unwrap();
+        let hero = match query.definitions[0] {
+            Definition::Operation(OperationDefinition::SelectionSet(ref selection_set)) => {
+                match selection_set.items[0] {
+                    Selection::Field(ref hero) => hero.clone(),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        };
+        let friends = match hero.selection_set.items[0] {
+            Selection::Field(ref friends) => friends.clone(),
+            _ => unreachable!(),
+        };
+        let context = ValidationContext::new(json::Map::new());
+        let error =
+            CoercionError::invalid_argument(&friends, "first", "Int", &context).in_field(&hero);
+
+        assert_eq!(
+            json::to_value(&ResponseError::from(error)).unwrap(),
+            json!({
+                "message": "Invalid value for argument first of hero.friends: expected Int, found \"ten\"",
+                "path": ["hero", "friends"],
+                "extensions": { "argument": "first", "expectedType": "Int", "value": "ten" },
+            })
+        );
+    }
+
+    #[test]
+    fn validation_error_conversion() {
+        let error = QueryValidationError::InvalidFieldArguments {
+            position: graphql_parser::Pos { line: 3, column: 17 },
+        };
+
+        assert_eq!(
+            json::to_value(&ResponseError::from(error)).unwrap(),
+            json!({
+                "message": "Invalid field arguments",
+                "locations": [{ "line": 3, "column": 17 }],
+            })
+        );
     }
 }
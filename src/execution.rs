@@ -0,0 +1,359 @@
+//! The end-to-end pipeline for a single request: parsing, validation, coercion and resolution.
+//!
+//! Most users should only need `execute`, which takes a request as sent by the client and returns the response, shaped as described in the [spec](https://github.com/facebook/graphql/blob/master/spec/Section%207%20--%20Response.md).
+
+use coercion::{CoerceQueryDocument, CoerceResponse, Pruning};
+use errors::{GqlError, ResponseError};
+use futures::future;
+use futures::prelude::*;
+use graphql_parser;
+use graphql_parser::query::Document;
+use query_validation::{validate_query_all, ValidationContext};
+use resolver::Resolver;
+use response::Resolved;
+use serde_json as json;
+use std::sync::Arc;
+
+/// A GraphQL request, in the shape used by clients over HTTP.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Request {
+    pub query: String,
+    #[serde(default)]
+    pub variables: Option<json::Map<String, json::Value>>,
+    #[serde(rename = "operationName", default)]
+    pub operation_name: Option<String>,
+}
+
+impl Request {
+    pub fn new(query: impl Into<String>) -> Request {
+        Request {
+            query: query.into(),
+            variables: None,
+            operation_name: None,
+        }
+    }
+}
+
+/// The `{data, errors}` response to a request.
+///
+/// `data` is `None` when the request failed before execution started (parsing, validation or coercion), in which case it is omitted from the serialized response.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ExecutionResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ResponseError>,
+}
+
+impl ExecutionResult {
+    pub fn from_error(error: GqlError) -> ExecutionResult {
+        ExecutionResult::from_errors(vec![error])
+    }
+
+    pub fn from_errors(errors: Vec<GqlError>) -> ExecutionResult {
+        ExecutionResult {
+            data: None,
+            errors: errors.into_iter().map(ResponseError::from).collect(),
+        }
+    }
+}
+
+/// All errors are reported in the `ExecutionResult`, so this future never fails.
+pub type ExecutionFuture = Box<Future<Item = ExecutionResult, Error = ()>>;
+
+/// Parses, validates and coerces the request against the schema, then hands the typed operations to the resolver.
+///
+/// The schema is shared with the response future, so it is only parsed once and never copied per request.
+/// The response is pruned to the shape of the query with the default `Pruning`.
+pub fn execute<R>(
+    schema: &Arc<graphql_parser::schema::Document>,
+    request: Request,
+    resolver: &R,
+) -> ExecutionFuture
+where
+    R: Resolver,
+    R::Schema: CoerceQueryDocument,
+    R::Responder: Default,
+{
+    execute_with_pruning(schema, request, resolver, Pruning::default())
+}
+
+/// Like `execute`, with a choice of how strictly the response is pruned to the shape of the query.
+pub fn execute_with_pruning<R>(
+    schema: &Arc<graphql_parser::schema::Document>,
+    request: Request,
+    resolver: &R,
+    pruning: Pruning,
+) -> ExecutionFuture
+where
+    R: Resolver,
+    R::Schema: CoerceQueryDocument,
+    R::Responder: Default,
+{
+    let (query, context, operations) = match prepare::<R::Schema>(schema, request) {
+        Ok(prepared) => prepared,
+        Err(errors) => return Box::new(future::ok(ExecutionResult::from_errors(errors))),
+    };
+
+    let resolved: Vec<_> = operations
+        .into_iter()
+        .map(|operation| resolver.resolve(operation, R::Responder::default()))
+        .collect();
+    let schema = Arc::clone(schema);
+
+    Box::new(future::join_all(resolved).then(move |result| {
+        let Resolved { value, errors } = match result {
+            Ok(results) => {
+                schema.coerce_response(&query, &context, merge_operation_results(results), pruning)
+            }
+            Err(errors) => Resolved {
+                value: json::Value::Null,
+                errors,
+            },
+        };
+
+        Ok(ExecutionResult {
+            data: Some(value),
+            errors: errors.into_iter().map(ResponseError::from).collect(),
+        })
+    }))
+}
+
+/// All the validation errors are reported at once, while parsing and coercion stop at the first error.
+fn prepare<Operation: CoerceQueryDocument>(
+    schema: &graphql_parser::schema::Document,
+    request: Request,
+) -> Result<(Document, ValidationContext, Vec<Operation>), Vec<GqlError>> {
+    let query =
+        graphql_parser::parse_query(&request.query).map_err(|err| vec![GqlError::from(err)])?;
+    let context = validate_query_all(
+        &query,
+        request.variables.unwrap_or_default(),
+        request.operation_name.as_ref().map(|name| name.as_str()),
+        schema,
+    )
+    .map_err(|errors| errors.into_iter().map(GqlError::from).collect::<Vec<_>>())?;
+    let operations =
+        Operation::coerce(&query, &context).map_err(|err| vec![GqlError::from(err)])?;
+    Ok((query, context, operations))
+}
+
+/// The data is null if a null bubbled up to the root of one of the operations.
+fn merge_operation_results(results: Vec<Resolved>) -> Resolved {
+    let mut merged = json::Map::new();
+    let mut errors = Vec::new();
+    let mut is_null = false;
+
+    for result in results {
+        match result.value {
+            json::Value::Object(map) => merged.extend(map),
+            json::Value::Null => is_null = true,
+            _ => (),
+        }
+        errors.extend(result.errors);
+    }
+
+    Resolved {
+        value: if is_null {
+            json::Value::Null
+        } else {
+            json::Value::Object(merged)
+        },
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coercion::CoercionError;
+    use graphql_parser::query::*;
+    use query_validation::{select_operation, ValidationContext};
+    use resolver::ResolverFuture;
+
+    const SCHEMA: &str = r##"
+    type Query {
+        greeting: String
+        farewell: String
+    }
+
+    schema {
+        query: Query
+    }
+    "##;
+
+    #[derive(Debug, PartialEq)]
+    enum Operation {
+        Query { selection: Vec<String> },
+    }
+
+    impl CoerceQueryDocument for Operation {
+        fn coerce(
+            document: &Document,
+            context: &ValidationContext,
+        ) -> Result<Vec<Operation>, CoercionError> {
+            match select_operation(document, context.operation_name()) {
+                Ok(OperationDefinition::Query(query)) => {
+                    let selection = query
+                        .selection_set
+                        .items
+                        .iter()
+                        .filter_map(|item| match item {
+                            Selection::Field(field) => Some(field.name.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    Ok(vec![Operation::Query { selection }])
+                }
+                _ => Err(CoercionError::new("expected a query")),
+            }
+        }
+    }
+
+    struct EchoResolver;
+
+    impl Resolver for EchoResolver {
+        type Schema = Operation;
+        type Responder = ();
+
+        fn resolve(&self, request: Operation, _responder: ()) -> ResolverFuture {
+            let Operation::Query { selection } = request;
+            let response: json::Map<String, json::Value> = selection
+                .into_iter()
+                .map(|name| (name.clone(), json::Value::String(name)))
+                .collect();
+            Box::new(future::ok(json::Value::Object(response).into()))
+        }
+    }
+
+    fn execute_to_json(request: Request) -> json::Value {
+        let schema = Arc::new(graphql_parser::parse_schema(SCHEMA).unwrap());
+        let result = execute(&schema, request, &EchoResolver).wait().unwrap();
+        json::to_value(&result).unwrap()
+    }
+
+    #[test]
+    fn request_deserialization() {
+        let request: Request = json::from_value(json!({
+            "query": "query Greet { greeting }",
+            "variables": { "polite": true },
+            "operationName": "Greet",
+        })).unwrap();
+
+        let mut variables = json::Map::new();
+        variables.insert("polite".to_string(), json!(true));
+
+        assert_eq!(
+            request,
+            Request {
+                query: "query Greet { greeting }".to_string(),
+                variables: Some(variables),
+                operation_name: Some("Greet".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn request_deserialization_with_null_variables() {
+        let request: Request = json::from_value(json!({
+            "query": "query { greeting }",
+            "variables": null,
+        })).unwrap();
+
+        assert_eq!(request, Request::new("query { greeting }"));
+    }
+
+    #[test]
+    fn successful_execution() {
+        assert_eq!(
+            execute_to_json(Request::new("query { greeting farewell }")),
+            json!({ "data": { "greeting": "greeting", "farewell": "farewell" } })
+        );
+    }
+
+    #[test]
+    fn operation_selection_by_name() {
+        let request = Request {
+            operation_name: Some("Farewell".to_string()),
+            ..Request::new("query Greeting { greeting } query Farewell { farewell }")
+        };
+
+        assert_eq!(
+            execute_to_json(request),
+            json!({ "data": { "farewell": "farewell" } })
+        );
+    }
+
+    #[test]
+    fn ambiguous_operations_are_reported() {
+        assert_eq!(
+            execute_to_json(Request::new(
+                "query Greeting { greeting } query Farewell { farewell }"
+            )),
+            json!({
+                "errors": [{
+                    "message": "The document contains several operations, the operation name must be provided",
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_operations_are_reported() {
+        let request = Request {
+            operation_name: Some("Welcome".to_string()),
+            ..Request::new("query Greeting { greeting }")
+        };
+
+        assert_eq!(
+            execute_to_json(request),
+            json!({ "errors": [{ "message": "Unknown operation Welcome" }] })
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let response = execute_to_json(Request::new("query { greeting "));
+
+        assert_eq!(response.get("data"), None);
+        assert_eq!(response["errors"].as_array().map(|errs| errs.len()), Some(1));
+    }
+
+    #[test]
+    fn validation_errors_are_reported() {
+        assert_eq!(
+            execute_to_json(Request::new("query { greeting thisFieldDoesNotExist }")),
+            json!({
+                "errors": [{
+                    "message": "Cannot query field thisFieldDoesNotExist on type Query",
+                    "locations": [{ "line": 1, "column": 18 }],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn all_validation_errors_are_reported() {
+        assert_eq!(
+            execute_to_json(Request::new(
+                "query($unused: Int) { greeting { length } farewell @shout }"
+            )),
+            json!({
+                "errors": [
+                    {
+                        "message": "Field greeting of type String cannot have a selection of subfields",
+                        "locations": [{ "line": 1, "column": 23 }],
+                    },
+                    {
+                        "message": "Unknown directive @shout",
+                        "locations": [{ "line": 1, "column": 52 }],
+                    },
+                    {
+                        "message": "Variable $unused is not used by the operation",
+                        "locations": [{ "line": 1, "column": 7 }],
+                    },
+                ]
+            })
+        );
+    }
+}
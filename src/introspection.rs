@@ -1,6 +1,56 @@
 //! These types are directly adapted from the introspection schema.
 //! See https://github.com/facebook/graphql/blob/master/spec/Section%204%20--%20Introspection.md
+//!
+//! The derive generates an `INTROSPECTION_SCHEMA` constant describing the schema with these types. The `introspection` crate answers `__schema` and `__type` queries from it.
 
+use graphql_parser::schema;
+
+/// The introspection types in SDL, along with the `__schema` and `__type` meta-fields of the query root type (on `__MetaFields`). Query validation uses it to validate introspection queries.
+pub const INTROSPECTION_SDL: &str = include_str!("introspection.graphql");
+
+/// The type the meta-fields are defined on in `INTROSPECTION_SDL`.
+pub const META_FIELDS_TYPE: &str = "__MetaFields";
+
+lazy_static! {
+    static ref INTROSPECTION_DOCUMENT: schema::Document =
+        ::graphql_parser::parse_schema(INTROSPECTION_SDL).expect("the introspection SDL is valid");
+}
+
+fn introspection_definitions() -> impl Iterator<Item = &'static schema::TypeDefinition> {
+    INTROSPECTION_DOCUMENT
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            schema::Definition::TypeDefinition(definition) => Some(definition),
+            _ => None,
+        })
+}
+
+/// Finds an introspection type by name. Schemas do not define them, but queries can select them in any operation. Like the meta-fields (see `query_validation::find_field`), they are validated whether or not the resolver answers introspection queries.
+pub fn introspection_type(name: &str) -> Option<&'static schema::TypeDefinition> {
+    introspection_definitions().find(|definition| match definition {
+        schema::TypeDefinition::Object(object) => {
+            object.name == name && object.name != META_FIELDS_TYPE
+        }
+        schema::TypeDefinition::Enum(enum_type) => enum_type.name == name,
+        _ => false,
+    })
+}
+
+/// Finds the `__schema` or `__type` meta-field by name.
+pub fn meta_field(name: &str) -> Option<&'static schema::Field> {
+    introspection_definitions()
+        .filter_map(|definition| match definition {
+            schema::TypeDefinition::Object(object) if object.name == META_FIELDS_TYPE => {
+                Some(&object.fields)
+            }
+            _ => None,
+        })
+        .flat_map(|fields| fields.iter())
+        .find(|field| field.name == name)
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Directive {
     pub name: &'static str,
     pub description: Option<&'static str>,
@@ -8,6 +58,8 @@ pub struct Directive {
     pub args: &'static [InputValue],
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TypeKind {
     Scalar,
     Object,
@@ -19,13 +71,33 @@ pub enum TypeKind {
     NonNull,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct InputValue {
     pub name: &'static str,
     pub description: Option<&'static str>,
-    pub type_: &'static str,
+    pub type_: TypeRef,
     pub default_value: Option<&'static str>,
 }
 
+/// The type of a field or input value: a named type, possibly wrapped in lists and non-null markers. `[Character!]!` for example is `NonNull(&List(&NonNull(&Named("Character"))))`.
+#[derive(Debug, PartialEq)]
+pub enum TypeRef {
+    Named(&'static str),
+    List(&'static TypeRef),
+    NonNull(&'static TypeRef),
+}
+
+impl TypeRef {
+    /// The name of the type, once unwrapped.
+    pub fn named_type(&self) -> &'static str {
+        match self {
+            TypeRef::Named(name) => *name,
+            TypeRef::List(inner) | TypeRef::NonNull(inner) => inner.named_type(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct EnumValue {
     pub name: &'static str,
     pub description: Option<&'static str>,
@@ -33,22 +105,34 @@ pub struct EnumValue {
     pub deprecation_reason: Option<&'static str>,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Field {
     pub name: &'static str,
     pub description: Option<&'static str>,
     pub args: &'static [InputValue],
-    pub type_: &'static str,
+    pub type_: TypeRef,
     pub is_deprecated: bool,
     pub deprecation_reason: Option<&'static str>,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Type {
     pub kind: TypeKind,
     pub name: Option<&'static str>,
     pub description: Option<&'static str>,
+    /// `OBJECT` and `INTERFACE` only.
     pub fields: &'static [Field],
+    /// The names of the interfaces an `OBJECT` implements.
+    pub interfaces: &'static [&'static str],
+    /// The names of the object types an `INTERFACE` or `UNION` can resolve to.
+    pub possible_types: &'static [&'static str],
+    /// `ENUM` only.
+    pub enum_values: &'static [EnumValue],
+    /// `INPUT_OBJECT` only.
+    pub input_fields: &'static [InputValue],
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Schema {
     pub types: &'static [Type],
     pub query_type: Option<&'static str>,
@@ -57,6 +141,14 @@ pub struct Schema {
     pub directives: &'static [Directive],
 }
 
+impl Schema {
+    pub fn type_by_name(&self, name: &str) -> Option<&'static Type> {
+        self.types.iter().find(|ty| ty.name == Some(name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DirectiveLocation {
     Query,
     Mutation,
@@ -4,22 +4,30 @@ extern crate futures;
 #[macro_use]
 extern crate matches;
 extern crate hyper;
+#[macro_use]
+extern crate lazy_static;
 extern crate serde;
 #[macro_use]
+extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 
 pub extern crate graphql_parser;
 
 pub mod coercion;
 pub mod errors;
+pub mod execution;
 pub mod identifiable;
 pub mod introspection;
 pub mod query_validation;
 pub mod resolver;
 pub mod response;
+pub mod service;
 mod shared;
 pub mod traits;
 
+pub use execution::execute;
+
 #[allow(unused_imports)]
 #[macro_use]
 extern crate something_different_derive;
@@ -1,13 +1,15 @@
 use graphql_parser;
 use graphql_parser::query::*;
+use graphql_parser::Pos;
 use graphql_parser::schema;
 use serde_json as json;
-use std::collections::HashMap;
+use std::collections::HashSet;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ValidationContext {
     fragment_definitions: Vec<FragmentDefinition>,
     variable_definitions: Vec<VariableDefinition>,
+    operation_name: Option<String>,
     pub variables: json::Map<String, json::Value>,
 }
 
@@ -18,10 +20,21 @@ impl ValidationContext {
         ValidationContext {
             fragment_definitions,
             variable_definitions,
+            operation_name: None,
             variables,
         }
     }
 
+    /// The name of the operation to execute, as passed in the request. It is only required when the document contains more than one operation.
+    pub fn with_operation_name(mut self, operation_name: Option<String>) -> ValidationContext {
+        self.operation_name = operation_name;
+        self
+    }
+
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_ref().map(|name| name.as_str())
+    }
+
     pub fn extend_variable_definitions(
         &mut self,
         defs: impl IntoIterator<Item = VariableDefinition>,
@@ -29,41 +42,268 @@ impl ValidationContext {
         self.variable_definitions.extend(defs.into_iter());
     }
 
+    pub fn variable_definition(&self, name: &str) -> Option<&VariableDefinition> {
+        self.variable_definitions
+            .iter()
+            .find(|definition| definition.name == name)
+    }
+
     pub fn push_fragment_definition(&mut self, definition: &FragmentDefinition) {
         self.fragment_definitions.push(definition.clone());
     }
+
+    pub fn fragment_definition(&self, name: &str) -> Option<&FragmentDefinition> {
+        self.fragment_definitions
+            .iter()
+            .find(|definition| definition.name == name)
+    }
 }
 
 #[derive(Debug, PartialEq, Fail)]
 pub enum QueryValidationError {
-    #[fail(display = "Invalid selection set")]
-    InvalidSelectionSet(SelectionSet),
+    #[fail(display = "Fields cannot be selected on type {}", parent_type)]
+    InvalidSelectionSet { parent_type: String, position: Pos },
+    #[fail(display = "Cannot query field {} on type {}", field, parent_type)]
+    UnknownField {
+        field: String,
+        parent_type: String,
+        position: Pos,
+    },
     #[fail(display = "Unknown directive {}", directive)]
     UnknownDirective { directive: Directive },
     #[fail(display = "Invalid field")]
     InvalidField,
     #[fail(display = "Invalid field arguments")]
-    InvalidFieldArguments,
+    InvalidFieldArguments { position: Pos },
     #[fail(display = "This operation is not defined for the schema: {}", operation)]
-    InvalidOperation { operation: &'static str },
+    InvalidOperation {
+        operation: &'static str,
+        position: Pos,
+    },
     #[fail(display = "Missing definition")]
     MissingDefinition,
     #[fail(display = "The following variable was not provided: {}", name)]
-    MissingVariable { name: String },
+    MissingVariable { name: String, position: Pos },
     #[fail(
         display = "Other error (if you see this it is a bug, a report would be very appreciated)"
     )]
     Other,
-    #[fail(display = "Variable mismatch")]
-    VariableMismatch,
+    #[fail(display = "Variable mismatch: {}", name)]
+    VariableMismatch { name: String, position: Pos },
+    #[fail(
+        display = "Unknown field {} of input type {} in {}",
+        field, input_type, path
+    )]
+    UnknownInputField {
+        input_type: String,
+        field: String,
+        path: String,
+        position: Pos,
+    },
+    #[fail(
+        display = "Missing required field {} of input type {} in {}",
+        field, input_type, path
+    )]
+    MissingInputField {
+        input_type: String,
+        field: String,
+        path: String,
+        position: Pos,
+    },
+    #[fail(
+        display = "Invalid value for {}: expected {}, found {}",
+        path, expected_type, value
+    )]
+    InvalidInputValue {
+        expected_type: String,
+        value: String,
+        path: String,
+        position: Pos,
+    },
+    #[fail(display = "Variable ${} is not defined by the operation", name)]
+    UndefinedVariable { name: String, position: Pos },
+    #[fail(
+        display = "Variable ${} cannot be of type {}, which is not an input type",
+        name, variable_type
+    )]
+    NonInputVariableType {
+        name: String,
+        variable_type: String,
+        position: Pos,
+    },
+    #[fail(display = "Variable ${} is not used by the operation", name)]
+    UnusedVariable { name: String, position: Pos },
+    #[fail(
+        display = "Variable ${} of type {} cannot be used where {} is expected",
+        name, variable_type, expected_type
+    )]
+    VariableInInvalidPosition {
+        name: String,
+        variable_type: String,
+        expected_type: String,
+        position: Pos,
+    },
+    #[fail(
+        display = "Field {} of type {} cannot have a selection of subfields",
+        field, field_type
+    )]
+    SelectionOnLeafField {
+        field: String,
+        field_type: String,
+        position: Pos,
+    },
+    #[fail(
+        display = "Field {} of type {} must have a selection of subfields",
+        field, field_type
+    )]
+    MissingSelectionSet {
+        field: String,
+        field_type: String,
+        position: Pos,
+    },
+    #[fail(display = "There can only be one fragment named {}", name)]
+    DuplicateFragment { name: String, position: Pos },
+    #[fail(display = "There can only be one argument named {}", name)]
+    DuplicateArgument { name: String, position: Pos },
+    #[fail(display = "Fragment {} is not used by any operation", name)]
+    UnusedFragment { name: String, position: Pos },
+    #[fail(display = "Fragment {} spreads itself", name)]
+    CyclicFragment { name: String, position: Pos },
+    #[fail(
+        display = "Fragments can only be on object, interface and union types, not on {}",
+        type_condition
+    )]
+    FragmentOnNonCompositeType {
+        type_condition: String,
+        position: Pos,
+    },
+    #[fail(display = "Unknown type {}", name)]
+    UnknownType { name: String, position: Pos },
+    #[fail(
+        display = "A fragment on {} can never apply to a value of type {}",
+        type_condition, parent_type
+    )]
+    ImpossibleFragmentSpread {
+        type_condition: String,
+        parent_type: String,
+        position: Pos,
+    },
+    #[fail(
+        display = "Fields {} conflict because {}. Use different aliases on the fields to fetch both",
+        response_key, reason
+    )]
+    FieldsConflict {
+        response_key: String,
+        reason: String,
+        position: Pos,
+    },
+    #[fail(display = "Unknown fragment {}", name)]
+    UnknownFragment { name: String, position: Pos },
+    #[fail(display = "Unknown operation {}", name)]
+    UnknownOperation { name: String },
+    #[fail(
+        display = "The document contains several operations, the operation name must be provided"
+    )]
+    AmbiguousOperation,
+    #[fail(display = "The document does not contain any operation")]
+    MissingOperation,
 }
 
+impl QueryValidationError {
+    /// The position in the query document the error originates from, if there is one.
+    pub fn position(&self) -> Option<Pos> {
+        match self {
+            QueryValidationError::UnknownDirective { directive } => Some(directive.position),
+            QueryValidationError::InvalidFieldArguments { position }
+            | QueryValidationError::InvalidSelectionSet { position, .. }
+            | QueryValidationError::UnknownField { position, .. }
+            | QueryValidationError::InvalidOperation { position, .. }
+            | QueryValidationError::MissingVariable { position, .. }
+            | QueryValidationError::VariableMismatch { position, .. }
+            | QueryValidationError::UnknownInputField { position, .. }
+            | QueryValidationError::MissingInputField { position, .. }
+            | QueryValidationError::InvalidInputValue { position, .. }
+            | QueryValidationError::UndefinedVariable { position, .. }
+            | QueryValidationError::NonInputVariableType { position, .. }
+            | QueryValidationError::UnusedVariable { position, .. }
+            | QueryValidationError::VariableInInvalidPosition { position, .. }
+            | QueryValidationError::UnknownType { position, .. }
+            | QueryValidationError::SelectionOnLeafField { position, .. }
+            | QueryValidationError::MissingSelectionSet { position, .. }
+            | QueryValidationError::DuplicateFragment { position, .. }
+            | QueryValidationError::DuplicateArgument { position, .. }
+            | QueryValidationError::UnusedFragment { position, .. }
+            | QueryValidationError::CyclicFragment { position, .. }
+            | QueryValidationError::FragmentOnNonCompositeType { position, .. }
+            | QueryValidationError::ImpossibleFragmentSpread { position, .. }
+            | QueryValidationError::FieldsConflict { position, .. }
+            | QueryValidationError::UnknownFragment { position, .. } => Some(*position),
+            QueryValidationError::InvalidField
+            | QueryValidationError::UnknownOperation { .. }
+            | QueryValidationError::AmbiguousOperation
+            | QueryValidationError::MissingOperation
+            | QueryValidationError::MissingDefinition
+            | QueryValidationError::Other => None,
+        }
+    }
+}
+
+/// Finds the operation to execute in a document: the one named `operation_name`, or the only operation in the document if no name is given.
+pub fn select_operation<'a>(
+    document: &'a Document,
+    operation_name: Option<&str>,
+) -> Result<&'a OperationDefinition, QueryValidationError> {
+    let mut operations = document.definitions.iter().filter_map(|definition| {
+        if let Definition::Operation(operation) = definition {
+            Some(operation)
+        } else {
+            None
+        }
+    });
+
+    match operation_name {
+        Some(operation_name) => operations
+            .find(|operation| {
+                let name = match operation {
+                    OperationDefinition::Query(query) => &query.name,
+                    OperationDefinition::Mutation(mutation) => &mutation.name,
+                    OperationDefinition::Subscription(subscription) => &subscription.name,
+                    OperationDefinition::SelectionSet(_) => &None,
+                };
+                name.as_ref().map(|name| name.as_str()) == Some(operation_name)
+            })
+            .ok_or_else(|| QueryValidationError::UnknownOperation {
+                name: operation_name.to_string(),
+            }),
+        None => match (operations.next(), operations.next()) {
+            (Some(operation), None) => Ok(operation),
+            (Some(_), Some(_)) => Err(QueryValidationError::AmbiguousOperation),
+            (None, _) => Err(QueryValidationError::MissingOperation),
+        },
+    }
+}
+
+/// Validates the operation selected by `operation_name` (see `select_operation`) against the schema, and returns the first error found. See `validate_query_all` to get all of them.
 pub fn validate_query(
     query: &graphql_parser::query::Document,
     variables: json::Map<String, json::Value>,
+    operation_name: Option<&str>,
     schema: &graphql_parser::schema::Document,
 ) -> Result<ValidationContext, QueryValidationError> {
-    let mut context = ValidationContext::new(variables);
+    validate_query_all(query, variables, operation_name, schema)
+        .map_err(|mut errors| errors.remove(0))
+}
+
+/// Validates the query against the schema, and returns all the errors found, in the order of the document. Validation stops early only when the rest of the document cannot be checked, for example when the operation to execute is unknown or fragments spread themselves.
+pub fn validate_query_all(
+    query: &graphql_parser::query::Document,
+    variables: json::Map<String, json::Value>,
+    operation_name: Option<&str>,
+    schema: &graphql_parser::schema::Document,
+) -> Result<ValidationContext, Vec<QueryValidationError>> {
+    let mut context = ValidationContext::new(variables)
+        .with_operation_name(operation_name.map(|name| name.to_string()));
+    let mut errors = ValidationErrors::default();
 
     let schema_definition = schema
         .definitions
@@ -76,106 +316,420 @@ pub fn validate_query(
             }
         })
         .next()
-        .ok_or(QueryValidationError::Other)?;
+        .ok_or_else(|| vec![QueryValidationError::Other])?;
 
+    // Fragments can be defined anywhere in the document, so they have to be known before the operations are validated.
     for definition in query.definitions.iter() {
-        match definition {
-            Definition::Operation(op) => match op {
-                OperationDefinition::Query(ref q) => match &schema_definition.query {
-                    Some(name) => {
-                        validate_variables(
-                            &mut context.variables,
-                            &q.variable_definitions,
-                            &schema,
-                        )?;
-                        find_by_name(&schema.definitions, name)?.validate_selection_set(
-                            &q.selection_set,
-                            &schema,
-                            &context,
-                        )?;
-                    }
-                    None => {
-                        return Err(QueryValidationError::InvalidOperation { operation: "query" })
-                    }
-                },
-                OperationDefinition::Mutation(ref m) => match &schema_definition.mutation {
-                    Some(name) => {
-                        validate_variables(
-                            &mut context.variables,
-                            &m.variable_definitions,
-                            &schema,
-                        )?;
-                        find_by_name(&schema.definitions, name)?.validate_selection_set(
-                            &m.selection_set,
-                            &schema,
-                            &context,
-                        )?;
-                    }
-                    None => {
-                        return Err(QueryValidationError::InvalidOperation {
-                            operation: "mutation",
-                        })
-                    }
-                },
-                OperationDefinition::Subscription(s) => match &schema_definition.subscription {
-                    Some(name) => {
-                        validate_variables(
-                            &mut context.variables,
-                            &s.variable_definitions,
-                            &schema,
-                        )?;
-                        find_by_name(&schema.definitions, name)?.validate_selection_set(
-                            &s.selection_set,
-                            &schema,
-                            &context,
-                        )?;
-                    }
-                    None => {
-                        return Err(QueryValidationError::InvalidOperation {
-                            operation: "subscription",
-                        })
-                    }
-                },
-                OperationDefinition::SelectionSet(_) => {
-                    return Err(QueryValidationError::InvalidOperation {
-                        operation: "selection set",
-                    })
+        if let Definition::Fragment(def) = definition {
+            if context.fragment_definition(&def.name).is_some() {
+                errors.push(QueryValidationError::DuplicateFragment {
+                    name: def.name.clone(),
+                    position: def.position,
+                });
+            } else {
+                context.push_fragment_definition(def);
+            }
+        }
+    }
+
+    validate_fragment_type_conditions(&context, schema, &mut errors);
+
+    // Selection sets spreading cyclic fragments cannot be traversed.
+    let cycles = fragment_cycles(&context);
+    if !cycles.is_empty() {
+        for cycle in cycles {
+            errors.push(cycle);
+        }
+        return Err(errors.0);
+    }
+
+    let (variable_definitions, selection_set, root_name) =
+        match select_operation(query, operation_name) {
+            Ok(OperationDefinition::Query(q)) => match &schema_definition.query {
+                Some(name) => (&q.variable_definitions[..], &q.selection_set, name),
+                None => {
+                    errors.push(QueryValidationError::InvalidOperation {
+                        operation: "query",
+                        position: q.position,
+                    });
+                    return Err(errors.0);
                 }
             },
-            Definition::Fragment(def) => {
-                context.push_fragment_definition(def);
+            Ok(OperationDefinition::Mutation(m)) => match &schema_definition.mutation {
+                Some(name) => (&m.variable_definitions[..], &m.selection_set, name),
+                None => {
+                    errors.push(QueryValidationError::InvalidOperation {
+                        operation: "mutation",
+                        position: m.position,
+                    });
+                    return Err(errors.0);
+                }
+            },
+            Ok(OperationDefinition::Subscription(s)) => match &schema_definition.subscription {
+                Some(name) => (&s.variable_definitions[..], &s.selection_set, name),
+                None => {
+                    errors.push(QueryValidationError::InvalidOperation {
+                        operation: "subscription",
+                        position: s.position,
+                    });
+                    return Err(errors.0);
+                }
+            },
+            // The `{ ... }` shorthand is an anonymous query without variables.
+            Ok(OperationDefinition::SelectionSet(set)) => match &schema_definition.query {
+                Some(name) => (&[][..], set, name),
+                None => {
+                    errors.push(QueryValidationError::InvalidOperation {
+                        operation: "query",
+                        position: set.span.0,
+                    });
+                    return Err(errors.0);
+                }
+            },
+            Err(err) => {
+                errors.push(err);
+                return Err(errors.0);
+            }
+        };
+
+    context.extend_variable_definitions(variable_definitions.iter().cloned());
+    let default_values = validate_variables(variable_definitions, schema, &context, &mut errors);
+    context.variables.extend(default_values);
+
+    let query_root = schema_definition.query.as_ref().map(|name| name.as_str());
+
+    match find_by_name(&schema.definitions, root_name) {
+        Ok(root) => {
+            root.validate_selection_set(selection_set, schema, query_root, &context, &mut errors);
+            validate_fields_can_merge(
+                root,
+                selection_set,
+                schema,
+                query_root,
+                &context,
+                &mut errors,
+            );
+        }
+        Err(err) => errors.push(err),
+    }
+
+    validate_variable_usages(selection_set, variable_definitions, &context, &mut errors);
+    validate_fragments_used(query, &context, &mut errors);
+
+    if errors.0.is_empty() {
+        Ok(context)
+    } else {
+        Err(errors.0)
+    }
+}
+
+/// The errors found while validating a document. An error is only recorded once, even when the fragment it comes from is spread in several places.
+#[derive(Default)]
+struct ValidationErrors(Vec<QueryValidationError>);
+
+impl ValidationErrors {
+    fn push(&mut self, error: QueryValidationError) {
+        if !self.0.contains(&error) {
+            self.0.push(error);
+        }
+    }
+
+    /// Records the error of a failed check, so validation can go on with the next one.
+    fn record<T>(&mut self, result: Result<T, QueryValidationError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push(err);
+                None
+            }
+        }
+    }
+}
+
+/// The names of the fragments spread in a selection set, with the positions of the spreads. The fragments themselves are not followed.
+fn fragment_spreads(set: &SelectionSet) -> Vec<(&str, Pos)> {
+    let mut spreads = Vec::new();
+
+    for selection in set.items.iter() {
+        match selection {
+            Selection::Field(field) => spreads.extend(fragment_spreads(&field.selection_set)),
+            Selection::FragmentSpread(spread) => {
+                spreads.push((spread.fragment_name.as_str(), spread.position))
+            }
+            Selection::InlineFragment(fragment) => {
+                spreads.extend(fragment_spreads(&fragment.selection_set))
+            }
+        }
+    }
+
+    spreads
+}
+
+/// Checks that fragments are on composite types of the schema. Spreads of unknown fragments are reported when the operation is validated.
+fn validate_fragment_type_conditions(
+    context: &ValidationContext,
+    schema: &graphql_parser::schema::Document,
+    errors: &mut ValidationErrors,
+) {
+    for fragment in context.fragment_definitions.iter() {
+        let TypeCondition::On(ref type_condition) = fragment.type_condition;
+        errors.record(fragment_condition_type(
+            type_condition,
+            fragment.position,
+            schema,
+        ));
+    }
+}
+
+/// Finds the fragments that spread themselves, directly or through other fragments. Each cycle is reported at the spread that closes it.
+fn fragment_cycles(context: &ValidationContext) -> Vec<QueryValidationError> {
+    fn visit<'a>(
+        fragment: &'a FragmentDefinition,
+        context: &'a ValidationContext,
+        path: &mut Vec<&'a str>,
+        explored: &mut Vec<&'a str>,
+        cycles: &mut Vec<QueryValidationError>,
+    ) {
+        path.push(&fragment.name);
+
+        for (name, position) in fragment_spreads(&fragment.selection_set) {
+            if path.contains(&name) {
+                cycles.push(QueryValidationError::CyclicFragment {
+                    name: name.to_string(),
+                    position,
+                });
+                continue;
             }
+
+            match context.fragment_definition(name) {
+                Some(spread) if !explored.contains(&name) => {
+                    visit(spread, context, path, explored, cycles)
+                }
+                _ => (),
+            }
+        }
+
+        path.pop();
+        explored.push(&fragment.name);
+    }
+
+    let mut explored = Vec::new();
+    let mut cycles = Vec::new();
+
+    for fragment in context.fragment_definitions.iter() {
+        if !explored.contains(&fragment.name.as_str()) {
+            visit(
+                fragment,
+                context,
+                &mut Vec::new(),
+                &mut explored,
+                &mut cycles,
+            );
+        }
+    }
+
+    cycles
+}
+
+/// Every fragment must be spread by at least one operation of the document, directly or through other fragments.
+fn validate_fragments_used(
+    query: &graphql_parser::query::Document,
+    context: &ValidationContext,
+    errors: &mut ValidationErrors,
+) {
+    let mut to_visit: Vec<&str> = query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(OperationDefinition::Query(query)) => Some(&query.selection_set),
+            Definition::Operation(OperationDefinition::Mutation(mutation)) => {
+                Some(&mutation.selection_set)
+            }
+            Definition::Operation(OperationDefinition::Subscription(subscription)) => {
+                Some(&subscription.selection_set)
+            }
+            Definition::Operation(OperationDefinition::SelectionSet(set)) => Some(set),
+            Definition::Fragment(_) => None,
+        })
+        .flat_map(|set| fragment_spreads(set).into_iter().map(|(name, _)| name))
+        .collect();
+    let mut used = Vec::new();
+
+    while let Some(name) = to_visit.pop() {
+        if used.contains(&name) {
+            continue;
+        }
+        used.push(name);
+
+        if let Some(fragment) = context.fragment_definition(name) {
+            to_visit.extend(
+                fragment_spreads(&fragment.selection_set)
+                    .into_iter()
+                    .map(|(name, _)| name),
+            );
         }
     }
 
-    Ok(context)
+    for fragment in context
+        .fragment_definitions
+        .iter()
+        .filter(|fragment| !used.contains(&fragment.name.as_str()))
+    {
+        errors.push(QueryValidationError::UnusedFragment {
+            name: fragment.name.clone(),
+            position: fragment.position,
+        });
+    }
 }
 
-fn find_by_name(
-    definitions: &[schema::Definition],
+/// Finds a field of an object or interface type. The query root type, named by `query_root`, also has the `__schema` and `__type` meta-fields, so introspection queries are validated like any other query.
+///
+/// The meta-fields are accepted on the query root of every schema, but only resolvers wrapped in `Introspected`, from the `introspection` crate, answer them. Other resolvers leave them out of the response.
+pub fn find_field<'a>(
+    parent_type: &'a schema::TypeDefinition,
     name: &str,
-) -> Result<impl Selectable, QueryValidationError> {
-    for definition in definitions.iter() {
-        match definition {
-            schema::Definition::TypeDefinition(schema::TypeDefinition::Object(def))
-                if def.name == name =>
+    query_root: Option<&str>,
+) -> Option<&'a schema::Field> {
+    let (type_name, fields) = match parent_type {
+        schema::TypeDefinition::Object(object) => (&object.name, &object.fields),
+        schema::TypeDefinition::Interface(interface) => (&interface.name, &interface.fields),
+        _ => return None,
+    };
+
+    fields.iter().find(|field| field.name == name).or_else(|| {
+        if query_root == Some(type_name.as_str()) {
+            ::introspection::meta_field(name)
+        } else {
+            None
+        }
+    })
+}
+
+fn type_definition_name(definition: &schema::TypeDefinition) -> &str {
+    match definition {
+        schema::TypeDefinition::Scalar(scalar) => &scalar.name,
+        schema::TypeDefinition::Object(object) => &object.name,
+        schema::TypeDefinition::Interface(interface) => &interface.name,
+        schema::TypeDefinition::Union(union) => &union.name,
+        schema::TypeDefinition::Enum(enum_type) => &enum_type.name,
+        schema::TypeDefinition::InputObject(input_object) => &input_object.name,
+    }
+}
+
+fn find_by_name<'a>(
+    definitions: &'a [schema::Definition],
+    name: &str,
+) -> Result<&'a schema::TypeDefinition, QueryValidationError> {
+    definitions
+        .iter()
+        .find_map(|definition| match definition {
+            schema::Definition::TypeDefinition(type_definition)
+                if type_definition_name(type_definition) == name =>
             {
-                return Ok(def.clone())
+                Some(type_definition)
             }
-            _ => (),
+            _ => None,
+        })
+        // Fragments can be on introspection types, in any operation.
+        .or_else(|| ::introspection::introspection_type(name))
+        .ok_or(QueryValidationError::MissingDefinition)
+}
+
+/// The object types a value of the given type can have: the type itself for objects, the objects implementing an interface, and the members of a union. Other types have none.
+fn possible_types<'a>(
+    definition: &'a schema::TypeDefinition,
+    schema: &'a graphql_parser::schema::Document,
+) -> Vec<&'a str> {
+    match definition {
+        schema::TypeDefinition::Object(object) => vec![object.name.as_str()],
+        schema::TypeDefinition::Interface(interface) => schema
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                schema::Definition::TypeDefinition(schema::TypeDefinition::Object(object))
+                    if object.implements_interfaces.contains(&interface.name) =>
+                {
+                    Some(object.name.as_str())
+                }
+                _ => None,
+            })
+            .collect(),
+        schema::TypeDefinition::Union(union) => union.types.iter().map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_composite(definition: &schema::TypeDefinition) -> bool {
+    match definition {
+        schema::TypeDefinition::Object(_)
+        | schema::TypeDefinition::Interface(_)
+        | schema::TypeDefinition::Union(_) => true,
+        _ => false,
+    }
+}
+
+/// Finds the type named by the type condition of a fragment, which must be a composite type.
+fn fragment_condition_type<'a>(
+    type_condition: &str,
+    position: Pos,
+    schema: &'a graphql_parser::schema::Document,
+) -> Result<&'a schema::TypeDefinition, QueryValidationError> {
+    let fragment_type = match find_by_name(&schema.definitions, type_condition) {
+        Ok(fragment_type) => Some(fragment_type),
+        // The built-in scalars are not defined in the schema document.
+        Err(_) if ["Int", "Float", "String", "Boolean", "ID"].contains(&type_condition) => None,
+        Err(_) => {
+            return Err(QueryValidationError::UnknownType {
+                name: type_condition.to_string(),
+                position,
+            })
         }
+    };
+
+    match fragment_type {
+        Some(fragment_type) if is_composite(fragment_type) => Ok(fragment_type),
+        _ => Err(QueryValidationError::FragmentOnNonCompositeType {
+            type_condition: type_condition.to_string(),
+            position,
+        }),
     }
+}
 
-    Err(QueryValidationError::MissingDefinition)
+/// Finds the type a fragment is on, and checks that the fragment can apply to values of `parent_type`: the two types must have at least one possible type in common.
+fn fragment_type<'a>(
+    type_condition: &str,
+    parent_type: &schema::TypeDefinition,
+    position: Pos,
+    schema: &'a graphql_parser::schema::Document,
+) -> Result<&'a schema::TypeDefinition, QueryValidationError> {
+    let fragment_type = fragment_condition_type(type_condition, position, schema)?;
+
+    let parent_possible_types = possible_types(parent_type, schema);
+    let applies = possible_types(fragment_type, schema)
+        .iter()
+        .any(|possible_type| parent_possible_types.contains(possible_type));
+
+    if applies {
+        Ok(fragment_type)
+    } else {
+        Err(QueryValidationError::ImpossibleFragmentSpread {
+            type_condition: type_condition.to_string(),
+            parent_type: type_definition_name(parent_type).to_string(),
+            position,
+        })
+    }
 }
 
 trait Selectable {
+    /// `query_root` is the name of the query root type, where the introspection meta-fields can be selected.
     fn validate_selection_set(
         &self,
         set: &SelectionSet,
         schema: &graphql_parser::schema::Document,
+        query_root: Option<&str>,
         context: &ValidationContext,
-    ) -> Result<(), QueryValidationError>;
+        errors: &mut ValidationErrors,
+    );
 }
 
 impl Selectable for schema::TypeDefinition {
@@ -183,31 +737,54 @@ impl Selectable for schema::TypeDefinition {
         &self,
         set: &SelectionSet,
         schema: &graphql_parser::schema::Document,
+        query_root: Option<&str>,
         context: &ValidationContext,
-    ) -> Result<(), QueryValidationError> {
+        errors: &mut ValidationErrors,
+    ) {
+        // Only `__typename` can be selected on unions, the other fields are selected through fragments.
         match self {
-            schema::TypeDefinition::Object(obj) => {
-                obj.validate_selection_set(set, &schema, &context)
+            schema::TypeDefinition::Object(_)
+            | schema::TypeDefinition::Interface(_)
+            | schema::TypeDefinition::Union(_) => (),
+            schema::TypeDefinition::Scalar(_) | schema::TypeDefinition::Enum(_)
+                if set.items.is_empty() =>
+            {
+                return
             }
-            _ => unimplemented!(),
-        }
-    }
-}
+            _ => {
+                return errors.push(QueryValidationError::InvalidSelectionSet {
+                    parent_type: type_definition_name(self).to_string(),
+                    position: set.span.0,
+                })
+            }
+        };
 
-impl Selectable for schema::ObjectType {
-    fn validate_selection_set(
-        &self,
-        set: &SelectionSet,
-        schema: &graphql_parser::schema::Document,
-        context: &ValidationContext,
-    ) -> Result<(), QueryValidationError> {
         for selected in set.items.iter() {
+            validate_directives(selected, errors);
+            validate_directive_arguments(selected, schema, context, errors);
+
             match selected {
+                Selection::Field(field) if field.name == "__typename" => {
+                    if !field.selection_set.items.is_empty() {
+                        errors.push(QueryValidationError::SelectionOnLeafField {
+                            field: field.name.clone(),
+                            field_type: "String!".to_string(),
+                            position: field.position,
+                        });
+                    }
+                }
                 Selection::Field(field) => {
-                    let schema_field = self.fields
-                        .iter()
-                        .find(|f| f.name == field.name)
-                        .ok_or_else(|| QueryValidationError::InvalidSelectionSet(set.clone()))?;
+                    let schema_field = match find_field(self, &field.name, query_root) {
+                        Some(schema_field) => schema_field,
+                        None => {
+                            errors.push(QueryValidationError::UnknownField {
+                                field: field.name.clone(),
+                                parent_type: type_definition_name(self).to_string(),
+                                position: field.position,
+                            });
+                            continue;
+                        }
+                    };
 
                     let mut required_arguments = schema_field.arguments.iter().filter(|arg| {
                         matches!(arg.value_type, graphql_parser::schema::Type::NonNullType(_))
@@ -219,166 +796,976 @@ impl Selectable for schema::ObjectType {
                                 && value != &graphql_parser::query::Value::Null
                         })
                     }) {
-                        return Err(QueryValidationError::InvalidFieldArguments);
+                        errors.push(QueryValidationError::InvalidFieldArguments {
+                            position: field.position,
+                        });
                     }
 
-                    validate_argument_types(&field.arguments, &schema_field.arguments, &context)?;
+                    validate_argument_types(
+                        &field.arguments,
+                        &schema_field.arguments,
+                        field.position,
+                        &schema,
+                        &context,
+                        errors,
+                    );
 
+                    // Fields of scalar and enum types are leaves, other fields need subfields.
                     let inner_name = ::shared::extract_inner_name(&schema_field.field_type);
-                    let field_type = find_by_name(&schema.definitions, inner_name).ok();
-                    if let Some(field_type) = field_type {
-                        field_type.validate_selection_set(&field.selection_set, &schema, &context)?;
+                    let field_type = find_by_name(&schema.definitions, inner_name)
+                        .ok()
+                        .filter(|field_type| is_composite(field_type));
+
+                    match (field_type, field.selection_set.items.is_empty()) {
+                        (Some(_), true) => errors.push(QueryValidationError::MissingSelectionSet {
+                            field: field.name.clone(),
+                            field_type: schema_field.field_type.to_string(),
+                            position: field.position,
+                        }),
+                        (Some(field_type), false) => field_type.validate_selection_set(
+                            &field.selection_set,
+                            &schema,
+                            query_root,
+                            &context,
+                            errors,
+                        ),
+                        (None, true) => (),
+                        (None, false) => errors.push(QueryValidationError::SelectionOnLeafField {
+                            field: field.name.clone(),
+                            field_type: schema_field.field_type.to_string(),
+                            position: field.position,
+                        }),
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    let fragment = match context.fragment_definition(&spread.fragment_name) {
+                        Some(fragment) => fragment,
+                        None => {
+                            errors.push(QueryValidationError::UnknownFragment {
+                                name: spread.fragment_name.clone(),
+                                position: spread.position,
+                            });
+                            continue;
+                        }
+                    };
+                    let TypeCondition::On(ref type_condition) = fragment.type_condition;
+
+                    if let Some(fragment_type) =
+                        errors.record(fragment_type(type_condition, self, spread.position, schema))
+                    {
+                        fragment_type.validate_selection_set(
+                            &fragment.selection_set,
+                            &schema,
+                            query_root,
+                            &context,
+                            errors,
+                        );
                     }
                 }
-                _ => return Err(QueryValidationError::InvalidSelectionSet(set.clone())),
+                Selection::InlineFragment(fragment) => match fragment.type_condition {
+                    Some(TypeCondition::On(ref type_condition)) => {
+                        if let Some(fragment_type) = errors.record(fragment_type(
+                            type_condition,
+                            self,
+                            fragment.position,
+                            schema,
+                        )) {
+                            fragment_type.validate_selection_set(
+                                &fragment.selection_set,
+                                &schema,
+                                query_root,
+                                &context,
+                                errors,
+                            );
+                        }
+                    }
+                    None => self.validate_selection_set(
+                        &fragment.selection_set,
+                        &schema,
+                        query_root,
+                        &context,
+                        errors,
+                    ),
+                },
             }
         }
-
-        Ok(())
     }
 }
 
-fn validate_argument_types(
-    field_arguments: &[(String, graphql_parser::query::Value)],
-    schema_arguments: &[graphql_parser::schema::InputValue],
+/// Checks that the fields selected with the same response key, in a selection set and through its fragments, can be merged into a single entry of the response.
+fn validate_fields_can_merge(
+    parent_type: &schema::TypeDefinition,
+    set: &SelectionSet,
+    schema: &graphql_parser::schema::Document,
+    query_root: Option<&str>,
     context: &ValidationContext,
-) -> Result<(), QueryValidationError> {
-    use graphql_parser::query::Value;
-    use graphql_parser::schema::Type;
+    errors: &mut ValidationErrors,
+) {
+    let mut merging = FieldMerging {
+        schema,
+        query_root,
+        context,
+        compared: HashSet::new(),
+        checked: HashSet::new(),
+        conflicts: Vec::new(),
+    };
 
-    for (name, value) in field_arguments {
-        let schema_argument = schema_arguments
-            .iter()
-            .find(|arg| arg.name.as_str() == name.as_str())
-            .ok_or(QueryValidationError::InvalidFieldArguments)?;
+    merging.check_selection_set(parent_type, set);
 
-        // Validate listness of arguments
-        if let Value::List(_) = value {
-            if !matches!(schema_argument.value_type, Type::ListType(_)) {
-                return Err(QueryValidationError::InvalidFieldArguments);
-            }
+    for conflict in merging.conflicts {
+        errors.push(conflict);
+    }
+}
+
+/// A field selected in a selection set, with its response key and the type it is selected on.
+type CollectedField<'a> = (&'a str, &'a schema::TypeDefinition, &'a Field);
+
+/// The state of the field merging rule. Fragments can be spread in many places, so the pairs of fields already compared and the selection sets already checked are remembered, to keep the work proportional to the size of the document. Both are identified by their position in the document.
+struct FieldMerging<'a> {
+    schema: &'a graphql_parser::schema::Document,
+    query_root: Option<&'a str>,
+    context: &'a ValidationContext,
+    /// Pairs of fields, and whether their parent types exclude each other.
+    compared: HashSet<(Pos, Pos, bool)>,
+    checked: HashSet<Pos>,
+    conflicts: Vec<QueryValidationError>,
+}
+
+impl<'a> FieldMerging<'a> {
+    fn check_selection_set(
+        &mut self,
+        parent_type: &'a schema::TypeDefinition,
+        set: &'a SelectionSet,
+    ) {
+        if !self.checked.insert(set.span.0) {
+            return;
         }
 
-        let valid = match value {
-            Value::Boolean(_) => {
-                ::shared::extract_inner_name(&schema_argument.value_type) == "Boolean"
-            }
-            Value::Float(_) => ::shared::extract_inner_name(&schema_argument.value_type) == "Float",
-            Value::Int(_) => ::shared::extract_inner_name(&schema_argument.value_type) == "Int",
-            Value::String(_) => {
-                ::shared::extract_inner_name(&schema_argument.value_type) == "String"
-            }
-            // TODO: implement input object literals validation
-            Value::Object(_obj) => true,
-            Value::Variable(variable_name) => {
-                context.variables.contains_key(variable_name)
-                // TODO: Validate that the variable is the right type.
+        let mut fields = Vec::new();
+        self.collect_fields(parent_type, set, &mut Vec::new(), &mut fields);
+        self.check_conflicts(&fields, false);
+
+        for (_, parent_type, field) in fields {
+            if let Some(field_type) = self.selection_type(parent_type, field) {
+                self.check_selection_set(field_type, &field.selection_set);
             }
-            Value::Enum(_) => unimplemented!("Enum validation"),
-            Value::Null | Value::List(_) => true,
-        };
+        }
+    }
+
+    /// Collects the fields of a selection set, including the fields of its fragments. Each fragment is only collected once.
+    fn collect_fields(
+        &self,
+        parent_type: &'a schema::TypeDefinition,
+        set: &'a SelectionSet,
+        spread_fragments: &mut Vec<&'a str>,
+        fields: &mut Vec<CollectedField<'a>>,
+    ) {
+        for selection in set.items.iter() {
+            match selection {
+                Selection::Field(field) => {
+                    let response_key = field.alias.as_ref().unwrap_or(&field.name);
+                    fields.push((response_key, parent_type, field));
+                }
+                Selection::FragmentSpread(spread) => {
+                    if spread_fragments.contains(&spread.fragment_name.as_str()) {
+                        continue;
+                    }
+                    spread_fragments.push(&spread.fragment_name);
+
+                    if let Some(fragment) = self.context.fragment_definition(&spread.fragment_name)
+                    {
+                        let TypeCondition::On(ref type_condition) = fragment.type_condition;
+                        if let Ok(fragment_type) =
+                            find_by_name(&self.schema.definitions, type_condition)
+                        {
+                            self.collect_fields(
+                                fragment_type,
+                                &fragment.selection_set,
+                                spread_fragments,
+                                fields,
+                            );
+                        }
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let fragment_type = match fragment.type_condition {
+                        Some(TypeCondition::On(ref type_condition)) => {
+                            find_by_name(&self.schema.definitions, type_condition).ok()
+                        }
+                        None => Some(parent_type),
+                    };
 
-        if !valid {
-            return Err(QueryValidationError::InvalidFieldArguments);
+                    if let Some(fragment_type) = fragment_type {
+                        self.collect_fields(
+                            fragment_type,
+                            &fragment.selection_set,
+                            spread_fragments,
+                            fields,
+                        );
+                    }
+                }
+            }
         }
     }
-    Ok(())
-}
 
-pub fn type_matches(
-    variable: &json::Value,
-    type_name: &str,
-    schema: &graphql_parser::schema::Document,
-) -> Result<(), QueryValidationError> {
-    use serde_json::Value;
+    fn check_conflicts(&mut self, fields: &[CollectedField<'a>], exclusive: bool) {
+        for (i, field) in fields.iter().enumerate() {
+            for other in fields[i + 1..].iter().filter(|other| other.0 == field.0) {
+                self.check_conflict(field, other, exclusive);
+            }
+        }
+    }
 
-    match variable {
-        Value::Array(_) => Err(QueryValidationError::VariableMismatch), // arrays are already handled in validate_variable
-        Value::Bool(b) => if type_name == "Boolean" {
-            Ok(())
-        } else {
-            Err(QueryValidationError::VariableMismatch)
-        },
-        Value::Null => Ok(()),
-        Value::Number(num) => if type_name == "Float" {
-            num.as_f64()
-                .ok_or(QueryValidationError::VariableMismatch)
-                .map(|_| ())
-        } else if type_name == "Int" {
-            num.as_i64()
-                .ok_or(QueryValidationError::VariableMismatch)
-                .map(|_| ())
+    /// Two fields with the same response key must select the same field with the same arguments, unless they are on different object types, in which case only one of them can be in the response. In both cases they must return values of the same shape, and their subfields must be mergeable too.
+    fn check_conflict(
+        &mut self,
+        field: &CollectedField<'a>,
+        other: &CollectedField<'a>,
+        exclusive: bool,
+    ) {
+        let (response_key, parent_type, field) = *field;
+        let (_, other_parent_type, other) = *other;
+
+        let (first, second) = (field.position, other.position);
+        let pair = if first < second {
+            (first, second, exclusive)
         } else {
-            Err(QueryValidationError::VariableMismatch)
-        },
-        Value::String(s) => if type_name == "String" {
+            (second, first, exclusive)
+        };
+        if first == second || !self.compared.insert(pair) {
+            return;
+        }
+
+        let exclusive = exclusive
+            || match (parent_type, other_parent_type) {
+                (schema::TypeDefinition::Object(object), schema::TypeDefinition::Object(other)) => {
+                    object.name != other.name
+                }
+                _ => false,
+            };
+        let conflict = |reason: String| QueryValidationError::FieldsConflict {
+            response_key: response_key.to_string(),
+            reason,
+            position: other.position,
+        };
+
+        if !exclusive {
+            if field.name != other.name {
+                return self.conflicts.push(conflict(format!(
+                    "{} and {} are different fields",
+                    field.name, other.name
+                )));
+            }
+
+            let same_arguments = field.arguments.len() == other.arguments.len()
+                && field
+                    .arguments
+                    .iter()
+                    .all(|argument| other.arguments.contains(argument));
+            if !same_arguments {
+                return self
+                    .conflicts
+                    .push(conflict("they have different arguments".to_string()));
+            }
+        }
+
+        let field_type = field_definition_type(parent_type, &field.name, self.query_root);
+        let other_type = field_definition_type(other_parent_type, &other.name, self.query_root);
+        if let (Some(field_type), Some(other_type)) = (field_type, other_type) {
+            if !self.same_response_shape(&field_type, &other_type) {
+                return self.conflicts.push(conflict(format!(
+                    "they return conflicting types {} and {}",
+                    field_type, other_type
+                )));
+            }
+        }
+
+        // The subfields of both fields end up in the same object of the response.
+        let mut subfields = Vec::new();
+        for &(parent_type, field) in [(parent_type, field), (other_parent_type, other)].iter() {
+            if let Some(field_type) = self.selection_type(parent_type, field) {
+                self.collect_fields(
+                    field_type,
+                    &field.selection_set,
+                    &mut Vec::new(),
+                    &mut subfields,
+                );
+            }
+        }
+
+        self.check_conflicts(&subfields, exclusive);
+    }
+
+    /// Leaf types must be identical, while the fields of composite types are compared separately.
+    fn same_response_shape(&self, field_type: &schema::Type, other_type: &schema::Type) -> bool {
+        use graphql_parser::schema::Type;
+
+        match (field_type, other_type) {
+            (Type::NonNullType(field_type), Type::NonNullType(other_type))
+            | (Type::ListType(field_type), Type::ListType(other_type)) => {
+                self.same_response_shape(field_type, other_type)
+            }
+            (Type::NamedType(field_type), Type::NamedType(other_type)) => {
+                let is_composite_type = |name: &str| {
+                    find_by_name(&self.schema.definitions, name)
+                        .map(is_composite)
+                        .unwrap_or(false)
+                };
+                field_type == other_type
+                    || (is_composite_type(field_type) && is_composite_type(other_type))
+            }
+            _ => false,
+        }
+    }
+
+    /// The composite type the subfields of a field are selected on.
+    fn selection_type(
+        &self,
+        parent_type: &schema::TypeDefinition,
+        field: &Field,
+    ) -> Option<&'a schema::TypeDefinition> {
+        let field_type = field_definition_type(parent_type, &field.name, self.query_root)?;
+        let inner_name = ::shared::extract_inner_name(&field_type);
+
+        find_by_name(&self.schema.definitions, inner_name)
+            .ok()
+            .filter(|field_type| is_composite(field_type))
+    }
+}
+
+/// The type of a field of an object or interface, including the `__typename` meta field.
+fn field_definition_type(
+    parent_type: &schema::TypeDefinition,
+    name: &str,
+    query_root: Option<&str>,
+) -> Option<schema::Type> {
+    if name == "__typename" {
+        return Some(schema::Type::NonNullType(Box::new(
+            schema::Type::NamedType("String".to_string()),
+        )));
+    }
+
+    find_field(parent_type, name, query_root).map(|field| field.field_type.clone())
+}
+
+/// Only the directives defined by the spec, `@skip` and `@include`, are supported in queries.
+fn validate_directives(selection: &Selection, errors: &mut ValidationErrors) {
+    let directives = match selection {
+        Selection::Field(field) => &field.directives,
+        Selection::FragmentSpread(spread) => &spread.directives,
+        Selection::InlineFragment(fragment) => &fragment.directives,
+    };
+
+    for directive in directives
+        .iter()
+        .filter(|directive| directive.name != "skip" && directive.name != "include")
+    {
+        errors.push(QueryValidationError::UnknownDirective {
+            directive: directive.clone(),
+        });
+    }
+}
+
+/// The `if` argument of `@skip` and `@include` is a `Boolean!`.
+fn validate_directive_arguments(
+    selection: &Selection,
+    schema: &graphql_parser::schema::Document,
+    context: &ValidationContext,
+    errors: &mut ValidationErrors,
+) {
+    let directives = match selection {
+        Selection::Field(field) => &field.directives,
+        Selection::FragmentSpread(spread) => &spread.directives,
+        Selection::InlineFragment(fragment) => &fragment.directives,
+    };
+    let condition_type =
+        schema::Type::NonNullType(Box::new(schema::Type::NamedType("Boolean".to_string())));
+
+    for directive in directives.iter() {
+        for (name, value) in directive.arguments.iter().filter(|(name, _)| name == "if") {
+            errors.record(validate_input_value(
+                value,
+                &condition_type,
+                false,
+                name,
+                directive.position,
+                schema,
+                context,
+            ));
+        }
+    }
+}
+
+fn validate_argument_types(
+    field_arguments: &[(String, graphql_parser::query::Value)],
+    schema_arguments: &[graphql_parser::schema::InputValue],
+    position: Pos,
+    schema: &graphql_parser::schema::Document,
+    context: &ValidationContext,
+    errors: &mut ValidationErrors,
+) {
+    for (index, (name, value)) in field_arguments.iter().enumerate() {
+        if field_arguments[..index].iter().any(|(other, _)| other == name) {
+            errors.push(QueryValidationError::DuplicateArgument {
+                name: name.clone(),
+                position,
+            });
+            continue;
+        }
+
+        errors.record(validate_argument_type(
+            name,
+            value,
+            schema_arguments,
+            position,
+            schema,
+            context,
+        ));
+    }
+}
+
+fn validate_argument_type(
+    name: &str,
+    value: &graphql_parser::query::Value,
+    schema_arguments: &[graphql_parser::schema::InputValue],
+    position: Pos,
+    schema: &graphql_parser::schema::Document,
+    context: &ValidationContext,
+) -> Result<(), QueryValidationError> {
+    use graphql_parser::query::Value;
+    use graphql_parser::schema::Type;
+
+    let schema_argument = schema_arguments
+        .iter()
+        .find(|arg| arg.name.as_str() == name)
+        .ok_or(QueryValidationError::InvalidFieldArguments { position })?;
+
+    // Validate listness of arguments
+    if let Value::List(_) = value {
+        let nullable_type = match schema_argument.value_type {
+            Type::NonNullType(ref inner) => inner,
+            ref other => other,
+        };
+
+        if !matches!(*nullable_type, Type::ListType(_)) {
+            return Err(QueryValidationError::InvalidFieldArguments { position });
+        }
+    }
+
+    validate_input_value(
+        value,
+        &schema_argument.value_type,
+        schema_argument.default_value.is_some(),
+        &schema_argument.name,
+        position,
+        schema,
+        context,
+    )
+}
+
+fn find_type_definition<'a>(
+    schema: &'a graphql_parser::schema::Document,
+    name: &str,
+) -> Option<&'a schema::TypeDefinition> {
+    find_by_name(&schema.definitions, name).ok()
+}
+
+/// Whether an integer fits in the 32 bits of a GraphQL `Int`.
+fn is_int(n: Option<i64>) -> bool {
+    match n {
+        Some(n) => n >= i64::from(i32::MIN) && n <= i64::from(i32::MAX),
+        None => false,
+    }
+}
+
+/// Whether a literal is a valid value for one of the built-in scalars. Integers are valid floats and IDs.
+fn builtin_scalar_accepts(type_name: &str, value: &graphql_parser::query::Value) -> bool {
+    use graphql_parser::query::Value;
+
+    match (type_name, value) {
+        ("Int", Value::Int(n)) => is_int(n.as_i64()),
+        ("Float", Value::Int(_))
+        | ("Float", Value::Float(_))
+        | ("String", Value::String(_))
+        | ("Boolean", Value::Boolean(_))
+        | ("ID", Value::String(_))
+        | ("ID", Value::Int(_)) => true,
+        _ => false,
+    }
+}
+
+/// Like `builtin_scalar_accepts`, for variable values.
+fn builtin_scalar_accepts_json(type_name: &str, value: &json::Value) -> bool {
+    use serde_json::Value;
+
+    match (type_name, value) {
+        ("Int", Value::Number(n)) => is_int(n.as_i64()),
+        ("ID", Value::Number(n)) => n.is_i64() || n.is_u64(),
+        ("Float", Value::Number(_))
+        | ("String", Value::String(_))
+        | ("Boolean", Value::Bool(_))
+        | ("ID", Value::String(_)) => true,
+        _ => false,
+    }
+}
+
+/// Checks the fields of an input object value: every field must be defined on `input_type`, and every non-null field without a default value must be present. The value of each field is checked with `validate_field`, which receives the definition and the path of the field.
+fn validate_input_object_fields<'a, V: 'a>(
+    fields: impl IntoIterator<Item = (&'a String, &'a V)>,
+    input_type: &schema::InputObjectType,
+    path: &str,
+    position: Pos,
+    mut validate_field: impl FnMut(&V, &schema::InputValue, &str) -> Result<(), QueryValidationError>,
+) -> Result<(), QueryValidationError> {
+    let mut present = Vec::new();
+
+    for (name, value) in fields {
+        let field = input_type
+            .fields
+            .iter()
+            .find(|field| &field.name == name)
+            .ok_or_else(|| QueryValidationError::UnknownInputField {
+                input_type: input_type.name.clone(),
+                field: name.clone(),
+                path: path.to_string(),
+                position,
+            })?;
+
+        validate_field(value, field, &format!("{}.{}", path, name))?;
+        present.push(name);
+    }
+
+    let missing = input_type.fields.iter().find(|field| {
+        matches!(field.value_type, schema::Type::NonNullType(_))
+            && field.default_value.is_none()
+            && !present.contains(&&field.name)
+    });
+
+    match missing {
+        Some(field) => Err(QueryValidationError::MissingInputField {
+            input_type: input_type.name.clone(),
+            field: field.name.clone(),
+            path: path.to_string(),
+            position,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Validates an argument value against its type, recursing into lists and input object literals. `has_default` tells whether the argument or input field `value` is given for has a default value. `path` is the argument name followed by the fields and list indices leading to `value`.
+fn validate_input_value(
+    value: &graphql_parser::query::Value,
+    expected_type: &schema::Type,
+    has_default: bool,
+    path: &str,
+    position: Pos,
+    schema: &graphql_parser::schema::Document,
+    context: &ValidationContext,
+) -> Result<(), QueryValidationError> {
+    use graphql_parser::query::Value;
+    use graphql_parser::schema::Type;
+
+    let invalid = || QueryValidationError::InvalidInputValue {
+        expected_type: expected_type.to_string(),
+        value: value.to_string(),
+        path: path.to_string(),
+        position,
+    };
+
+    if let Value::Variable(name) = value {
+        return validate_variable_position(name, expected_type, has_default, position, context);
+    }
+
+    let nullable_type = match (expected_type, value) {
+        (Type::NonNullType(_), Value::Null) => return Err(invalid()),
+        (Type::NonNullType(inner), _) => inner,
+        (_, Value::Null) => return Ok(()),
+        (other, _) => other,
+    };
+
+    match (nullable_type, value) {
+        (Type::ListType(item_type), Value::List(items)) => {
+            for (index, item) in items.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                validate_input_value(
+                    item, item_type, false, &item_path, position, schema, context,
+                )?;
+            }
             Ok(())
-        } else {
-            Err(QueryValidationError::VariableMismatch)
+        }
+        // A single value is coerced to a list of one item.
+        (Type::ListType(item_type), _) => {
+            validate_input_value(value, item_type, false, path, position, schema, context)
+        }
+        (Type::NamedType(name), _) => match (find_type_definition(schema, name), value) {
+            (Some(schema::TypeDefinition::InputObject(input_type)), Value::Object(fields)) => {
+                validate_input_object_fields(
+                    fields,
+                    input_type,
+                    path,
+                    position,
+                    |value, field, path| {
+                        validate_input_value(
+                            value,
+                            &field.value_type,
+                            field.default_value.is_some(),
+                            path,
+                            position,
+                            schema,
+                            context,
+                        )
+                    },
+                )
+            }
+            (Some(schema::TypeDefinition::Enum(enum_type)), Value::Enum(variant))
+                if enum_type.values.iter().any(|value| &value.name == variant) =>
+            {
+                Ok(())
+            }
+            // Custom scalars define their own literals.
+            (Some(schema::TypeDefinition::Scalar(_)), _) => Ok(()),
+            (None, _) if builtin_scalar_accepts(name, value) => Ok(()),
+            _ => Err(invalid()),
         },
-        Value::Object(_) => unimplemented!("object variable validation"),
+        // The grammar does not allow non-null types to be nested.
+        (Type::NonNullType(_), _) => Err(invalid()),
     }
 }
 
-pub fn validate_variable(
-    variable: &json::Value,
-    expected_type: &graphql_parser::schema::Type,
-    schema: &graphql_parser::schema::Document,
+/// Implements the "variables in allowed position" rule: the type of the variable must be compatible with the type expected where it is used. A nullable variable can be used where a non-null value is expected only when the variable or the argument has a default value.
+fn validate_variable_position(
+    name: &str,
+    expected_type: &schema::Type,
+    has_default: bool,
+    position: Pos,
+    context: &ValidationContext,
 ) -> Result<(), QueryValidationError> {
     use graphql_parser::schema::Type;
 
-    match expected_type {
-        Type::NamedType(name) => type_matches(variable, name, schema),
-        Type::NonNullType(inner) => {
-            if let json::Value::Null = variable {
-                Err(QueryValidationError::MissingVariable {
-                    name: "<unavailable>".to_string(),
-                })
-            } else {
-                validate_variable(variable, inner, schema)
+    let definition = context.variable_definition(name).ok_or_else(|| {
+        QueryValidationError::UndefinedVariable {
+            name: name.to_string(),
+            position,
+        }
+    })?;
+
+    let allowed = match (&definition.var_type, expected_type) {
+        (Type::NonNullType(_), _) | (_, Type::ListType(_)) | (_, Type::NamedType(_)) => {
+            types_are_compatible(&definition.var_type, expected_type)
+        }
+        (variable_type, Type::NonNullType(expected_inner)) => {
+            let variable_has_default = match definition.default_value {
+                Some(Value::Null) | None => false,
+                Some(_) => true,
+            };
+
+            (has_default || variable_has_default)
+                && types_are_compatible(variable_type, expected_inner)
+        }
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(QueryValidationError::VariableInInvalidPosition {
+            name: name.to_string(),
+            variable_type: definition.var_type.to_string(),
+            expected_type: expected_type.to_string(),
+            position,
+        })
+    }
+}
+
+fn types_are_compatible(variable_type: &schema::Type, expected_type: &schema::Type) -> bool {
+    use graphql_parser::schema::Type;
+
+    match (variable_type, expected_type) {
+        (Type::NonNullType(variable_inner), Type::NonNullType(expected_inner)) => {
+            types_are_compatible(variable_inner, expected_inner)
+        }
+        (_, Type::NonNullType(_)) => false,
+        (Type::NonNullType(variable_inner), _) => {
+            types_are_compatible(variable_inner, expected_type)
+        }
+        (Type::ListType(variable_item), Type::ListType(expected_item)) => {
+            types_are_compatible(variable_item, expected_item)
+        }
+        (Type::NamedType(variable_name), Type::NamedType(expected_name)) => {
+            variable_name == expected_name
+        }
+        _ => false,
+    }
+}
+
+/// Checks that every variable used in the operation, including in the fragments it spreads, is defined, and that every defined variable is used.
+fn validate_variable_usages(
+    set: &SelectionSet,
+    definitions: &[VariableDefinition],
+    context: &ValidationContext,
+    errors: &mut ValidationErrors,
+) {
+    let mut usages = Vec::new();
+    collect_variable_usages(set, context, &mut Vec::new(), &mut usages);
+
+    for (name, position) in usages.iter().filter(|(name, _)| {
+        !definitions
+            .iter()
+            .any(|definition| &definition.name == name)
+    }) {
+        errors.push(QueryValidationError::UndefinedVariable {
+            name: name.clone(),
+            position: *position,
+        });
+    }
+
+    for definition in definitions
+        .iter()
+        .filter(|definition| !usages.iter().any(|(name, _)| name == &definition.name))
+    {
+        errors.push(QueryValidationError::UnusedVariable {
+            name: definition.name.clone(),
+            position: definition.position,
+        });
+    }
+}
+
+/// Collects the names of the variables used in `set` with the position of their field or directive. Each fragment is only visited once.
+fn collect_variable_usages<'a>(
+    set: &'a SelectionSet,
+    context: &'a ValidationContext,
+    visited_fragments: &mut Vec<&'a str>,
+    usages: &mut Vec<(String, Pos)>,
+) {
+    fn collect_value(value: &Value, position: Pos, usages: &mut Vec<(String, Pos)>) {
+        match value {
+            Value::Variable(name) => usages.push((name.clone(), position)),
+            Value::List(items) => for item in items.iter() {
+                collect_value(item, position, usages);
+            },
+            Value::Object(fields) => for field in fields.values() {
+                collect_value(field, position, usages);
+            },
+            _ => (),
+        }
+    }
+
+    for selection in set.items.iter() {
+        let directives = match selection {
+            Selection::Field(field) => &field.directives,
+            Selection::FragmentSpread(spread) => &spread.directives,
+            Selection::InlineFragment(fragment) => &fragment.directives,
+        };
+
+        for directive in directives.iter() {
+            for (_, value) in directive.arguments.iter() {
+                collect_value(value, directive.position, usages);
             }
         }
-        Type::ListType(elem_type) => match variable {
-            json::Value::Array(inner) => {
-                for value in inner.iter() {
-                    let _ = validate_variable(value, elem_type, schema)?;
+
+        match selection {
+            Selection::Field(field) => {
+                for (_, value) in field.arguments.iter() {
+                    collect_value(value, field.position, usages);
                 }
+                collect_variable_usages(&field.selection_set, context, visited_fragments, usages);
+            }
+            Selection::FragmentSpread(spread) => {
+                if visited_fragments.contains(&spread.fragment_name.as_str()) {
+                    continue;
+                }
+                visited_fragments.push(&spread.fragment_name);
+
+                if let Some(fragment) = context.fragment_definition(&spread.fragment_name) {
+                    collect_variable_usages(
+                        &fragment.selection_set,
+                        context,
+                        visited_fragments,
+                        usages,
+                    );
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                collect_variable_usages(
+                    &fragment.selection_set,
+                    context,
+                    visited_fragments,
+                    usages,
+                );
+            }
+        }
+    }
+}
+
+/// Validates a variable value against the type it is expected to have, recursing into lists and input objects. `path` is the variable name followed by the fields and list indices leading to `value`.
+fn validate_json_input_value(
+    value: &json::Value,
+    expected_type: &schema::Type,
+    path: &str,
+    position: Pos,
+    schema: &graphql_parser::schema::Document,
+) -> Result<(), QueryValidationError> {
+    use graphql_parser::schema::Type;
+
+    let invalid = || QueryValidationError::InvalidInputValue {
+        expected_type: expected_type.to_string(),
+        value: value.to_string(),
+        path: path.to_string(),
+        position,
+    };
+
+    let nullable_type = match (expected_type, value) {
+        (Type::NonNullType(_), json::Value::Null) => return Err(invalid()),
+        (Type::NonNullType(inner), _) => inner,
+        (_, json::Value::Null) => return Ok(()),
+        (other, _) => other,
+    };
+
+    match (nullable_type, value) {
+        (Type::ListType(item_type), json::Value::Array(items)) => {
+            for (index, item) in items.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                validate_json_input_value(item, item_type, &item_path, position, schema)?;
+            }
+            Ok(())
+        }
+        (Type::ListType(item_type), _) => {
+            validate_json_input_value(value, item_type, path, position, schema)
+        }
+        (Type::NamedType(name), _) => match (find_type_definition(schema, name), value) {
+            (
+                Some(schema::TypeDefinition::InputObject(input_type)),
+                json::Value::Object(fields),
+            ) => validate_input_object_fields(
+                fields,
+                input_type,
+                path,
+                position,
+                |value, field, path| {
+                    validate_json_input_value(value, &field.value_type, path, position, schema)
+                },
+            ),
+            (Some(schema::TypeDefinition::Enum(enum_type)), json::Value::String(variant))
+                if enum_type.values.iter().any(|value| &value.name == variant) =>
+            {
                 Ok(())
             }
-            _ => Err(QueryValidationError::VariableMismatch)?,
+            (Some(schema::TypeDefinition::Scalar(_)), _) => Ok(()),
+            (None, _) if builtin_scalar_accepts_json(name, value) => Ok(()),
+            _ => Err(invalid()),
         },
+        (Type::NonNullType(_), _) => Err(invalid()),
+    }
+}
+
+/// Validates the value of a variable against its declared type.
+pub fn validate_variable(
+    variable: &json::Value,
+    expected_type: &graphql_parser::schema::Type,
+    definition: &VariableDefinition,
+    schema: &graphql_parser::schema::Document,
+) -> Result<(), QueryValidationError> {
+    use graphql_parser::schema::Type;
+
+    // A null value for a non-null variable is reported like a missing value.
+    if let (Type::NonNullType(_), json::Value::Null) = (expected_type, variable) {
+        return Err(QueryValidationError::MissingVariable {
+            name: definition.name.to_string(),
+            position: definition.position,
+        });
     }
+
+    validate_json_input_value(
+        variable,
+        expected_type,
+        &format!("${}", definition.name),
+        definition.position,
+        schema,
+    )
 }
 
+/// Validates the provided values and the default values of the variables against their types, which must be input types. Returns the default values of the variables that were not provided.
 fn validate_variables(
-    variables: &mut json::Map<String, json::Value>,
     definitions: &[VariableDefinition],
     schema: &graphql_parser::schema::Document,
-) -> Result<(), QueryValidationError> {
+    context: &ValidationContext,
+    errors: &mut ValidationErrors,
+) -> json::Map<String, json::Value> {
     use graphql_parser::schema::Type;
 
-    let mut default_values = HashMap::new();
+    let mut default_values = json::Map::new();
 
     for definition in definitions.iter() {
+        if let Err(err) = validate_variable_type(definition, schema) {
+            errors.push(err);
+            continue;
+        }
+
+        if let Some(default_value) = &definition.default_value {
+            errors.record(validate_input_value(
+                default_value,
+                &definition.var_type,
+                false,
+                &format!("${}", definition.name),
+                definition.position,
+                schema,
+                context,
+            ));
+        }
+
         match (
             &definition.var_type,
-            variables.get(&definition.name),
+            context.variables.get(&definition.name),
             &definition.default_value,
         ) {
-            (_, Some(val), _) => validate_variable(val, &definition.var_type, schema)?,
+            (_, Some(val), _) => {
+                errors.record(validate_variable(
+                    val,
+                    &definition.var_type,
+                    definition,
+                    schema,
+                ));
+            }
             (_, None, Some(val)) => {
-                default_values.insert(definition.name.to_string(), query_value_to_json(val)?);
+                if let Some(default_value) = errors.record(query_value_to_json(val)) {
+                    default_values.insert(definition.name.to_string(), default_value);
+                }
+            }
+            (Type::NonNullType(_), None, None) => {
+                errors.push(QueryValidationError::MissingVariable {
+                    name: definition.name.to_string(),
+                    position: definition.position,
+                })
             }
-            (Type::NonNullType(_), None, None) => Err(QueryValidationError::MissingVariable {
-                name: definition.name.to_string(),
-            })?,
             (_, None, None) => (),
         }
     }
 
-    variables.extend(default_values);
+    default_values
+}
+
+/// Variables can only be of scalar, enum and input object types.
+fn validate_variable_type(
+    definition: &VariableDefinition,
+    schema: &graphql_parser::schema::Document,
+) -> Result<(), QueryValidationError> {
+    let name = ::shared::extract_inner_name(&definition.var_type);
 
-    Ok(())
+    match find_type_definition(schema, name) {
+        Some(schema::TypeDefinition::Scalar(_))
+        | Some(schema::TypeDefinition::Enum(_))
+        | Some(schema::TypeDefinition::InputObject(_)) => Ok(()),
+        // The built-in scalars are not defined in the schema document.
+        None if ["Int", "Float", "String", "Boolean", "ID"].contains(&name) => Ok(()),
+        None => Err(QueryValidationError::UnknownType {
+            name: name.to_string(),
+            position: definition.position,
+        }),
+        Some(_) => Err(QueryValidationError::NonInputVariableType {
+            name: definition.name.clone(),
+            variable_type: definition.var_type.to_string(),
+            position: definition.position,
+        }),
+    }
 }
 
 pub fn query_value_to_json(
@@ -388,17 +1775,16 @@ pub fn query_value_to_json(
 
     match value {
         Value::Boolean(b) => Ok(json::Value::Bool(*b)),
-        Value::Enum(variant) => {
-            let variant = variant.as_str();
-            Ok(json!({ variant: null }))
-        }
+        // Like in variables, enum values are represented by their name.
+        Value::Enum(variant) => Ok(json!(variant)),
         Value::Float(n) => Ok(json!(n)),
         Value::Int(n) => {
-            let n = n.as_i64().unwrap();
+            let n = n.as_i64().ok_or(QueryValidationError::Other)?;
             Ok(json!(n))
         }
         Value::String(s) => Ok(json!(s)),
-        Value::Variable(_) => unreachable!("variable in variable definition"),
+        // The parser rejects variables in default values, but documents can also be built by hand.
+        Value::Variable(_) => Err(QueryValidationError::Other),
         Value::List(items) => {
             let inner: Result<Vec<json::Value>, _> =
                 items.iter().map(query_value_to_json).collect();
@@ -433,12 +1819,25 @@ mod tests {
             let parsed_schema = graphql_parser::parse_schema(schema).unwrap();
 
             assert_eq!(
-                validate_query(&parsed_query, json::Map::new(), &parsed_schema),
+                validate_query(&parsed_query, json::Map::new(), None, &parsed_schema),
                 expected
             );
         };
     }
 
+    /// Validates `query` with the given variables, which have to be a JSON object.
+    fn validate_with_variables(
+        query: &Document,
+        variables: json::Value,
+        schema: &graphql_parser::schema::Document,
+    ) -> Result<ValidationContext, QueryValidationError> {
+        let variables = match variables {
+            json::Value::Object(map) => map,
+            _ => panic!("variables should be an object"),
+        };
+        validate_query(query, variables, None, schema)
+    }
+
     #[test]
     fn inexistent_operation_query() {
         assert_validates! {
@@ -457,7 +1856,10 @@ mod tests {
                 query: FruitQuery
             }
             "## =>
-            Err(QueryValidationError::InvalidOperation { operation: "mutation" })
+            Err(QueryValidationError::InvalidOperation {
+                operation: "mutation",
+                position: Pos { line: 2, column: 13 },
+            })
         }
     }
 
@@ -495,24 +1897,20 @@ mod tests {
     }
 
     #[test]
-    fn missing_arguments() {
+    fn typename_meta_field() {
         assert_validates! {
             r##"
             query {
                 dogs {
+                    __typename
+                    kind: __typename
                     name
-                    age
-                    furDensity
-                    barks
                 }
             }
             "##,
             r##"
             type Dog {
                 name: String!
-                age(dogYears: Boolean!): Int
-                furDensity: Int
-                barks: Boolean
             }
 
             type Query {
@@ -523,27 +1921,37 @@ mod tests {
                 query: Query
             }
             "## =>
-            Err(QueryValidationError::InvalidFieldArguments)
+            Ok(ValidationContext::new(json::Map::new()))
         }
     }
 
     #[test]
-    fn wrong_argument_type() {
+    fn introspection_meta_fields() {
         assert_validates! {
             r##"
             query {
+                __schema {
+                    queryType { name }
+                    types {
+                        kind
+                        name
+                        fields(includeDeprecated: true) {
+                            name
+                            type { name }
+                        }
+                    }
+                }
+                __type(name: "Dog") {
+                    name
+                }
                 dogs {
-                    age(dogYears: 10)
-                    furDensity
+                    name
                 }
             }
             "##,
             r##"
             type Dog {
                 name: String!
-                age(dogYears: Boolean!): Int
-                furDensity: Int
-                barks: Boolean
             }
 
             type Query {
@@ -554,41 +1962,1305 @@ mod tests {
                 query: Query
             }
             "## =>
-            Err(QueryValidationError::InvalidFieldArguments)
+            Ok(ValidationContext::new(json::Map::new()))
         }
     }
 
     #[test]
-    fn wrong_argument_listness() {
-        assert_validates! {
-            r##"
+    fn invalid_introspection_query() {
+        let query = r##"
             query {
-                dogs {
-                    age(dogYears: [true])
+                __schema {
+                    queryType { name }
+                    rootValue
+                }
+            }
+            "##;
+        let schema = r##"
+            type Query {
+                dogs: [String!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##;
+
+        let parsed_query = graphql_parser::parse_query(query).unwrap();
+        let parsed_schema = graphql_parser::parse_schema(schema).unwrap();
+        let error =
+            validate_query(&parsed_query, json::Map::new(), None, &parsed_schema).unwrap_err();
+
+        assert_eq!(
+            error,
+            QueryValidationError::UnknownField {
+                field: "rootValue".to_string(),
+                parent_type: "__Schema".to_string(),
+                position: Pos { line: 5, column: 21 },
+            }
+        );
+    }
+
+    #[test]
+    fn missing_arguments() {
+        assert_validates! {
+            r##"
+            query {
+                dogs {
+                    name
+                    age
+                    furDensity
+                    barks
+                }
+            }
+            "##,
+            r##"
+            type Dog {
+                name: String!
+                age(dogYears: Boolean!): Int
+                furDensity: Int
+                barks: Boolean
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "## =>
+            Err(QueryValidationError::InvalidFieldArguments {
+                position: Pos { line: 5, column: 21 },
+            })
+        }
+    }
+
+    #[test]
+    fn wrong_argument_type() {
+        assert_validates! {
+            r##"
+            query {
+                dogs {
+                    age(dogYears: 10)
                     furDensity
                 }
             }
             "##,
             r##"
-            type Dog {
-                name: String!
-                age(dogYears: Boolean!): Int
-                furDensity: Int
-                barks: Boolean
+            type Dog {
+                name: String!
+                age(dogYears: Boolean!): Int
+                furDensity: Int
+                barks: Boolean
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "## =>
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "Boolean!".to_string(),
+                value: "10".to_string(),
+                path: "dogYears".to_string(),
+                position: Pos { line: 4, column: 21 },
+            })
+        }
+    }
+
+    #[test]
+    fn duplicate_argument() {
+        assert_eq!(
+            validate_characters_query(r##"query { search(text: "R2", text: "C3") { __typename } }"##),
+            Err("There can only be one argument named text".to_string())
+        );
+    }
+
+    #[test]
+    fn wrong_argument_listness() {
+        assert_validates! {
+            r##"
+            query {
+                dogs {
+                    age(dogYears: [true])
+                    furDensity
+                }
+            }
+            "##,
+            r##"
+            type Dog {
+                name: String!
+                age(dogYears: Boolean!): Int
+                furDensity: Int
+                barks: Boolean
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "## =>
+            Err(QueryValidationError::InvalidFieldArguments {
+                position: Pos { line: 4, column: 21 },
+            })
+        }
+    }
+
+    #[test]
+    fn unknown_fragment_spread() {
+        assert_validates! {
+            r##"
+            query {
+                dogs {
+                    ...dogFields
+                }
+            }
+
+            fragment catFields on Dog {
+                name
+            }
+            "##,
+            r##"
+            type Dog {
+                name: String!
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "## =>
+            Err(QueryValidationError::UnknownFragment {
+                name: "dogFields".to_string(),
+                position: Pos { line: 4, column: 24 },
+            })
+        }
+    }
+
+    #[test]
+    fn unknown_directive() {
+        let query = r##"
+            query {
+                dogs {
+                    name @uppercase
+                }
+            }
+            "##;
+        let schema = r##"
+            type Dog {
+                name: String!
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##;
+
+        let parsed_query = graphql_parser::parse_query(query).unwrap();
+        let parsed_schema = graphql_parser::parse_schema(schema).unwrap();
+        let error =
+            validate_query(&parsed_query, json::Map::new(), None, &parsed_schema).unwrap_err();
+
+        assert!(matches!(
+            error,
+            QueryValidationError::UnknownDirective { ref directive } if directive.name == "uppercase"
+        ));
+        assert_eq!(error.position(), Some(Pos { line: 4, column: 26 }));
+    }
+
+    #[test]
+    fn enum_argument_literals() {
+        let schema = r##"
+            enum DogCommand {
+                SIT
+                HEEL
+            }
+
+            type Dog {
+                knows(command: DogCommand!): Boolean!
+                age(dogYears: Boolean): Int
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##;
+
+        assert_validates! {
+            "query { dogs { knows(command: SIT) } }",
+            schema =>
+            Ok(ValidationContext::new(json::Map::new()))
+        }
+
+        assert_validates! {
+            "query { dogs { age(dogYears: SIT) } }",
+            schema =>
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "Boolean".to_string(),
+                value: "SIT".to_string(),
+                path: "dogYears".to_string(),
+                position: Pos { line: 1, column: 16 },
+            })
+        }
+
+        assert_validates! {
+            "query { dogs { knows(command: STAY) } }",
+            schema =>
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "DogCommand!".to_string(),
+                value: "STAY".to_string(),
+                path: "command".to_string(),
+                position: Pos { line: 1, column: 16 },
+            })
+        }
+    }
+
+    #[test]
+    fn scalar_argument_literals() {
+        let schema = r##"
+            type Dog {
+                name: String!
+            }
+
+            type Query {
+                dog(id: ID!): Dog
+                dogs(weight: Float, limit: Int): [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##;
+
+        for query in [
+            r##"query { dog(id: "1000") { name } }"##,
+            "query { dog(id: 1000) { name } }",
+            "query { dogs(weight: 1) { name } }",
+            "query { dogs(weight: 1.5, limit: 2147483647) { name } }",
+        ].iter()
+        {
+            assert_validates! {
+                query,
+                schema =>
+                Ok(ValidationContext::new(json::Map::new()))
+            }
+        }
+
+        assert_validates! {
+            "query { dogs(limit: 2147483648) { name } }",
+            schema =>
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "Int".to_string(),
+                value: "2147483648".to_string(),
+                path: "limit".to_string(),
+                position: Pos { line: 1, column: 9 },
+            })
+        }
+
+        assert_validates! {
+            "query { dog(id: 1.5) { name } }",
+            schema =>
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "ID!".to_string(),
+                value: "1.5".to_string(),
+                path: "id".to_string(),
+                position: Pos { line: 1, column: 9 },
+            })
+        }
+    }
+
+    #[test]
+    fn scalar_variables() {
+        let query = graphql_parser::parse_query(
+            "query($id: ID!, $limit: Int, $weights: [Float!]) { dog(id: $id) { name } dogs(limit: $limit, weights: $weights) { name } }",
+        ).unwrap();
+        let schema = graphql_parser::parse_schema(
+            r##"
+            type Dog {
+                name: String!
+            }
+
+            type Query {
+                dog(id: ID!): Dog
+                dogs(limit: Int, weights: [Float!]): [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##,
+        ).unwrap();
+        let validate = |variables| validate_with_variables(&query, variables, &schema).map(|_| ());
+
+        assert_eq!(validate(json!({ "id": "1000" })), Ok(()));
+        assert_eq!(validate(json!({ "id": 1000, "limit": 10, "weights": [1, 2.5] })), Ok(()));
+        assert_eq!(
+            validate(json!({ "id": "1000", "limit": 2147483648i64 })),
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "Int".to_string(),
+                value: "2147483648".to_string(),
+                path: "$limit".to_string(),
+                position: Pos { line: 1, column: 17 },
+            })
+        );
+        assert_eq!(
+            validate(json!({ "id": "1000", "weights": [1, "2"] })),
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "Float!".to_string(),
+                value: r#""2""#.to_string(),
+                path: "$weights[1]".to_string(),
+                position: Pos { line: 1, column: 30 },
+            })
+        );
+    }
+
+    #[test]
+    fn enum_variables() {
+        let query = graphql_parser::parse_query(
+            "query($command: DogCommand!, $commands: [DogCommand!]) { dogs { knows(command: $command) obeys(commands: $commands) } }",
+        ).unwrap();
+        let schema = graphql_parser::parse_schema(
+            r##"
+            enum DogCommand {
+                SIT
+                HEEL
+            }
+
+            type Dog {
+                knows(command: DogCommand!): Boolean!
+                obeys(commands: [DogCommand!]): Boolean!
+            }
+
+            type Query {
+                dogs: [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##,
+        ).unwrap();
+        let validate = |variables| {
+            validate_with_variables(&query, variables, &schema).map_err(|err| err.to_string())
+        };
+
+        assert!(validate(json!({ "command": "SIT", "commands": ["SIT", "HEEL"] })).is_ok());
+        assert_eq!(
+            validate(json!({ "command": "STAY", "commands": null })),
+            Err("Invalid value for $command: expected DogCommand!, found \"STAY\"".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "command": "SIT", "commands": ["HEEL", "sit"] })),
+            Err("Invalid value for $commands[1]: expected DogCommand!, found \"sit\"".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "command": 1, "commands": null })),
+            Err("Invalid value for $command: expected DogCommand!, found 1".to_string())
+        );
+    }
+
+    #[test]
+    fn object_variables() {
+        let query = r##"
+            query($filter: DogFilter, $name: String) {
+                dogs(filter: $filter, name: $name) {
+                    name
+                }
+            }
+            "##;
+        let schema = r##"
+            input DogFilter {
+                minAge: Int
+            }
+
+            type Dog {
+                name: String!
+            }
+
+            type Query {
+                dogs(filter: DogFilter, name: String): [Dog!]!
+            }
+
+            schema {
+                query: Query
+            }
+            "##;
+
+        let parsed_query = graphql_parser::parse_query(query).unwrap();
+        let parsed_schema = graphql_parser::parse_schema(schema).unwrap();
+        let validate = |variables| validate_with_variables(&parsed_query, variables, &parsed_schema);
+
+        assert!(validate(json!({ "filter": { "minAge": 3 }, "name": "Rex" })).is_ok());
+        assert_eq!(
+            validate(json!({ "filter": null, "name": { "first": "Rex" } })),
+            Err(QueryValidationError::InvalidInputValue {
+                expected_type: "String".to_string(),
+                value: r#"{"first":"Rex"}"#.to_string(),
+                path: "$name".to_string(),
+                position: Pos { line: 2, column: 39 },
+            })
+        );
+    }
+
+    const RECIPES_SCHEMA: &str = r##"
+        enum Unit {
+            GRAM
+            PINCH
+        }
+
+        input Quantity {
+            amount: Float!
+            unit: Unit = GRAM
+        }
+
+        input Ingredient {
+            name: String!
+            quantity: Quantity
+            substitutes: [[String!]]
+        }
+
+        input RecipeInput {
+            title: String!
+            servings: Int
+            ingredients: [Ingredient!]!
+        }
+
+        type Recipe {
+            title: String!
+        }
+
+        type Query {
+            recipes(filter: [Ingredient!]): [Recipe!]!
+        }
+
+        type Mutation {
+            createRecipe(recipe: RecipeInput!): Recipe
+        }
+
+        schema {
+            query: Query
+            mutation: Mutation
+        }
+        "##;
+
+    #[test]
+    fn valid_input_object_literals() {
+        assert_validates! {
+            r##"
+            mutation {
+                createRecipe(recipe: {
+                    title: "Pesto",
+                    ingredients: [
+                        { name: "basil", quantity: { amount: 50, unit: GRAM } },
+                        { name: "salt", quantity: { amount: 1.5, unit: PINCH }, substitutes: [["soy sauce"], null] },
+                        { name: "pine nuts", substitutes: "cashews" },
+                    ],
+                }) {
+                    title
+                }
+            }
+            "##,
+            RECIPES_SCHEMA =>
+            Ok(ValidationContext::new(json::Map::new()))
+        }
+
+        assert_validates! {
+            r##"query { recipes(filter: [{ name: "basil" }]) { title } }"##,
+            RECIPES_SCHEMA =>
+            Ok(ValidationContext::new(json::Map::new()))
+        }
+    }
+
+    #[test]
+    fn invalid_input_object_literals() {
+        let cases = vec![
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", ingredients: [], author: "me" }) { title } }"##,
+                "Unknown field author of input type RecipeInput in recipe",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto" }) { title } }"##,
+                "Missing required field ingredients of input type RecipeInput in recipe",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", ingredients: [{ quantity: { amount: 2 } }] }) { title } }"##,
+                "Missing required field name of input type Ingredient in recipe.ingredients[0]",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", ingredients: [{ name: "basil", quantity: { amount: "a lot" } }] }) { title } }"##,
+                "Invalid value for recipe.ingredients[0].quantity.amount: expected Float!, found \"a lot\"",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", ingredients: [{ name: "basil", quantity: { amount: 1, unit: HANDFUL } }] }) { title } }"##,
+                "Invalid value for recipe.ingredients[0].quantity.unit: expected Unit, found HANDFUL",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", ingredients: [{ name: "salt", substitutes: [["soy sauce", null]] }] }) { title } }"##,
+                "Invalid value for recipe.ingredients[0].substitutes[0][1]: expected String!, found null",
+                12,
+            ),
+            (
+                r##"mutation { createRecipe(recipe: { title: "Pesto", servings: 4000000000, ingredients: [] }) { title } }"##,
+                "Invalid value for recipe.servings: expected Int, found 4000000000",
+                12,
+            ),
+            (
+                r##"query { recipes(filter: [{ name: "basil" }, null]) { title } }"##,
+                "Invalid value for filter[1]: expected Ingredient!, found null",
+                9,
+            ),
+        ];
+
+        let schema = graphql_parser::parse_schema(RECIPES_SCHEMA).unwrap();
+
+        for (query, expected_message, column) in cases {
+            let query = graphql_parser::parse_query(query).unwrap();
+            let error = validate_query(&query, json::Map::new(), None, &schema).unwrap_err();
+
+            assert_eq!(error.to_string(), expected_message);
+            assert_eq!(error.position(), Some(Pos { line: 1, column }));
+        }
+    }
+
+    #[test]
+    fn input_object_variables() {
+        let query = graphql_parser::parse_query(
+            "mutation($recipe: RecipeInput!) { createRecipe(recipe: $recipe) { title } }",
+        ).unwrap();
+        let schema = graphql_parser::parse_schema(RECIPES_SCHEMA).unwrap();
+        let validate = |variables| {
+            validate_with_variables(&query, variables, &schema).map_err(|err| err.to_string())
+        };
+
+        assert!(
+            validate(json!({
+                "recipe": {
+                    "title": "Pesto",
+                    "servings": 2,
+                    "ingredients": [
+                        { "name": "basil", "quantity": { "amount": 50, "unit": "GRAM" } },
+                        { "name": "salt", "substitutes": [["soy sauce"]] },
+                    ],
+                },
+            })).is_ok()
+        );
+        assert_eq!(
+            validate(json!({ "recipe": { "title": "Pesto", "ingredients": [], "author": "me" } })),
+            Err("Unknown field author of input type RecipeInput in $recipe".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "recipe": { "ingredients": [] } })),
+            Err("Missing required field title of input type RecipeInput in $recipe".to_string())
+        );
+        assert_eq!(
+            validate(json!({
+                "recipe": {
+                    "title": "Pesto",
+                    "ingredients": [{ "name": "basil", "quantity": { "amount": 1, "unit": "HANDFUL" } }],
+                },
+            })),
+            Err("Invalid value for $recipe.ingredients[0].quantity.unit: expected Unit, found \"HANDFUL\"".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "recipe": { "title": "Pesto", "ingredients": [{ "name": ["basil"] }] } })),
+            Err("Invalid value for $recipe.ingredients[0].name: expected String!, found [\"basil\"]".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "recipe": "Pesto" })),
+            Err("Invalid value for $recipe: expected RecipeInput!, found \"Pesto\"".to_string())
+        );
+    }
+
+    #[test]
+    fn variables_in_allowed_positions() {
+        let schema = graphql_parser::parse_schema(RECIPES_SCHEMA).unwrap();
+        let validate = |query: &str, variables| {
+            let query = graphql_parser::parse_query(query).unwrap();
+            validate_with_variables(&query, variables, &schema)
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        };
+
+        let valid = vec![
+            (
+                "mutation($recipe: RecipeInput!) { createRecipe(recipe: $recipe) { title } }",
+                json!({ "recipe": { "title": "Pesto", "ingredients": [] } }),
+            ),
+            (
+                r##"mutation($recipe: RecipeInput = { title: "Pesto", ingredients: [] }) { createRecipe(recipe: $recipe) { title } }"##,
+                json!({}),
+            ),
+            (
+                r##"mutation($title: String!, $servings: Int, $amount: Float!) {
+                    createRecipe(recipe: { title: $title, servings: $servings, ingredients: [{ name: $title, quantity: { amount: $amount } }] }) { title }
+                }"##,
+                json!({ "title": "Pesto", "amount": 3 }),
+            ),
+            (
+                "query($ingredients: [Ingredient!]) { recipes(filter: $ingredients) { title } }",
+                json!({}),
+            ),
+            (
+                "query($ingredient: Ingredient!) { recipes(filter: [$ingredient]) { title } }",
+                json!({ "ingredient": { "name": "basil" } }),
+            ),
+            (
+                "query($ingredient: Ingredient!, $hide: Boolean!) { ...recipes } fragment recipes on Query { recipes(filter: [$ingredient]) { title @skip(if: $hide) } }",
+                json!({ "ingredient": { "name": "basil" }, "hide": false }),
+            ),
+            (
+                r##"query($unit: Unit = PINCH) { recipes(filter: [{ name: "salt", quantity: { amount: 1, unit: $unit } }]) { title } }"##,
+                json!({}),
+            ),
+        ];
+
+        for (query, variables) in valid {
+            assert_eq!(validate(query, variables), Ok(()), "{}", query);
+        }
+
+        let invalid = vec![
+            (
+                "mutation($recipe: RecipeInput) { createRecipe(recipe: $recipe) { title } }",
+                "Variable $recipe of type RecipeInput cannot be used where RecipeInput! is expected",
+            ),
+            (
+                "mutation($recipe: RecipeInput = null) { createRecipe(recipe: $recipe) { title } }",
+                "Variable $recipe of type RecipeInput cannot be used where RecipeInput! is expected",
+            ),
+            (
+                r##"mutation($servings: String) { createRecipe(recipe: { title: "Pesto", servings: $servings, ingredients: [] }) { title } }"##,
+                "Variable $servings of type String cannot be used where Int is expected",
+            ),
+            (
+                "query($ingredients: [Ingredient]) { recipes(filter: $ingredients) { title } }",
+                "Variable $ingredients of type [Ingredient] cannot be used where [Ingredient!] is expected",
+            ),
+            (
+                "query($ingredient: Ingredient) { recipes(filter: [$ingredient]) { title } }",
+                "Variable $ingredient of type Ingredient cannot be used where Ingredient! is expected",
+            ),
+            (
+                "query($hide: Boolean) { recipes { title @skip(if: $hide) } }",
+                "Variable $hide of type Boolean cannot be used where Boolean! is expected",
+            ),
+            (
+                "query { recipes(filter: $ingredients) { title } }",
+                "Variable $ingredients is not defined by the operation",
+            ),
+            (
+                "query { ...recipes } fragment recipes on Query { recipes { title @include(if: $show) } }",
+                "Variable $show is not defined by the operation",
+            ),
+            (
+                "query($ingredients: [Ingredient!], $unused: Int) { recipes(filter: $ingredients) { title } }",
+                "Variable $unused is not used by the operation",
+            ),
+            (
+                r##"query($unit: Unit = "PINCH") { recipes(filter: [{ name: "salt", quantity: { amount: 1, unit: $unit } }]) { title } }"##,
+                r##"Invalid value for $unit: expected Unit, found "PINCH""##,
+            ),
+            (
+                r##"mutation($servings: Int = { a: 1 }) { createRecipe(recipe: { title: "Pesto", servings: $servings, ingredients: [] }) { title } }"##,
+                "Invalid value for $servings: expected Int, found {a: 1}",
+            ),
+            (
+                "mutation($recipe: RecipeInput! = null) { createRecipe(recipe: $recipe) { title } }",
+                "Invalid value for $recipe: expected RecipeInput!, found null",
+            ),
+            (
+                "mutation($recipe: Recipe!) { createRecipe(recipe: $recipe) { title } }",
+                "Variable $recipe cannot be of type Recipe!, which is not an input type",
+            ),
+            (
+                "mutation($recipe: Recipes!) { createRecipe(recipe: $recipe) { title } }",
+                "Unknown type Recipes",
+            ),
+        ];
+
+        for (query, expected_message) in invalid {
+            assert_eq!(
+                validate(query, json!({})),
+                Err(expected_message.to_string()),
+                "{}",
+                query
+            );
+        }
+    }
+
+    const CHARACTERS_SCHEMA: &str = r##"
+        interface Character {
+            name: String!
+            friends: [Character]
+        }
+
+        type Human implements Character {
+            name: String!
+            friends: [Character]
+            homePlanet: String
+        }
+
+        type Droid implements Character {
+            name: String!
+            friends: [Character]
+            primaryFunction: String
+        }
+
+        type Starship {
+            name: String!
+            length(unit: String): Float
+        }
+
+        union SearchResult = Human | Droid | Starship
+
+        type Query {
+            hero: Character
+            search(text: String!): [SearchResult!]!
+        }
+
+        schema {
+            query: Query
+        }
+        "##;
+
+    fn validate_characters_query(query: &str) -> Result<(), String> {
+        let schema = graphql_parser::parse_schema(CHARACTERS_SCHEMA).unwrap();
+        let query = graphql_parser::parse_query(query).unwrap();
+
+        validate_query(&query, json::Map::new(), None, &schema)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn selections_on_interfaces_and_unions() {
+        let valid = vec![
+            "query { hero { name friends { __typename name } } }",
+            "query { hero { ... on Droid { primaryFunction } ... on Human { homePlanet friends { name } } } }",
+            "query { hero { ... { name } ...humanFields } } fragment humanFields on Human { homePlanet }",
+            "query { hero { ...characterFields } } fragment characterFields on Character { name }",
+            r##"query { search(text: "R2") { __typename ... on Character { name } ... on Starship { length(unit: "m") } } }"##,
+            r##"query { search(text: "R2") { ...results } } fragment results on SearchResult { ... on Droid { name } }"##,
+            r##"query { hero { ... on SearchResult { ... on Human { homePlanet } } } }"##,
+        ];
+
+        for query in valid {
+            assert_eq!(validate_characters_query(query), Ok(()), "{}", query);
+        }
+    }
+
+    #[test]
+    fn invalid_selections_on_interfaces_and_unions() {
+        let cases = vec![
+            (
+                "query { hero { primaryFunction } }",
+                "Cannot query field primaryFunction on type Character",
+            ),
+            (
+                r##"query { search(text: "R2") { name } }"##,
+                "Cannot query field name on type SearchResult",
+            ),
+            (
+                "query { hero { ... on Droid { homePlanet } } }",
+                "Cannot query field homePlanet on type Droid",
+            ),
+            (
+                r##"query { search(text: "R2") { ... on Starship { length(unit: 3) } } }"##,
+                "Invalid value for unit: expected String, found 3",
+            ),
+            (
+                "query { hero { ... on Starship { name } } }",
+                "A fragment on Starship can never apply to a value of type Character",
+            ),
+            (
+                "query { hero { ...ships } } fragment ships on Starship { name }",
+                "A fragment on Starship can never apply to a value of type Character",
+            ),
+            (
+                "query { hero { ... on Droid { ... on Human { name } } } }",
+                "A fragment on Human can never apply to a value of type Droid",
+            ),
+            (
+                "query { hero { ... on Wookiee { name } } }",
+                "Unknown type Wookiee",
+            ),
+        ];
+
+        for (query, expected_message) in cases {
+            assert_eq!(
+                validate_characters_query(query),
+                Err(expected_message.to_string()),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn selection_on_leaf_field() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    name {
+                        length
+                    }
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::SelectionOnLeafField {
+                field: "name".to_string(),
+                field_type: "String!".to_string(),
+                position: Pos { line: 4, column: 21 },
+            })
+        }
+    }
+
+    #[test]
+    fn selection_on_typename() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    __typename {
+                        name
+                    }
+                }
             }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::SelectionOnLeafField {
+                field: "__typename".to_string(),
+                field_type: "String!".to_string(),
+                position: Pos { line: 4, column: 21 },
+            })
+        }
+    }
 
-            type Query {
-                dogs: [Dog!]!
+    #[test]
+    fn missing_selection_set() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    friends
+                }
             }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::MissingSelectionSet {
+                field: "friends".to_string(),
+                field_type: "[Character]".to_string(),
+                position: Pos { line: 4, column: 21 },
+            })
+        }
+    }
 
-            schema {
-                query: Query
+    #[test]
+    fn shorthand_query() {
+        assert_eq!(
+            validate_characters_query("{ hero { name ...humanFields } } fragment humanFields on Human { homePlanet }"),
+            Ok(())
+        );
+        assert_eq!(
+            validate_characters_query("{ hero { friends } }"),
+            Err("Field friends of type [Character] must have a selection of subfields".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicate_fragment() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ...heroFields
+                }
             }
-            "## =>
-            Err(QueryValidationError::InvalidFieldArguments)
+
+            fragment heroFields on Character {
+                name
+            }
+
+            fragment heroFields on Character {
+                friends {
+                    name
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::DuplicateFragment {
+                name: "heroFields".to_string(),
+                position: Pos { line: 12, column: 13 },
+            })
+        }
+    }
+
+    #[test]
+    fn unused_fragment() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ...heroFields
+                }
+            }
+
+            fragment heroFields on Character {
+                ...nameField
+            }
+
+            fragment nameField on Character {
+                name
+            }
+
+            fragment friendFields on Character {
+                friends {
+                    name
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::UnusedFragment {
+                name: "friendFields".to_string(),
+                position: Pos { line: 16, column: 13 },
+            })
+        }
+    }
+
+    #[test]
+    fn cyclic_fragment() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ...heroFields
+                }
+            }
+
+            fragment heroFields on Character {
+                friends {
+                    ...friendFields
+                }
+            }
+
+            fragment friendFields on Character {
+                ... on Human {
+                    ...heroFields
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::CyclicFragment {
+                name: "heroFields".to_string(),
+                position: Pos { line: 16, column: 24 },
+            })
+        }
+    }
+
+    #[test]
+    fn fragment_on_non_composite_type() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    name {
+                        ...stringFields
+                    }
+                }
+            }
+
+            fragment stringFields on String {
+                length
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FragmentOnNonCompositeType {
+                type_condition: "String".to_string(),
+                position: Pos { line: 10, column: 13 },
+            })
+        }
+
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ... on String {
+                        length
+                    }
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FragmentOnNonCompositeType {
+                type_condition: "String".to_string(),
+                position: Pos { line: 4, column: 25 },
+            })
+        }
+    }
+
+    #[test]
+    fn fragment_on_unknown_type() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ...wookieeFields
+                }
+            }
+
+            fragment wookieeFields on Wookiee {
+                name
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::UnknownType {
+                name: "Wookiee".to_string(),
+                position: Pos { line: 8, column: 13 },
+            })
+        }
+    }
+
+    #[test]
+    fn mergeable_fields() {
+        let valid = vec![
+            "query { hero { name name } }",
+            "query { hero { name ...nameField } } fragment nameField on Character { name }",
+            "query { hero { ... on Human { label: homePlanet } ... on Droid { label: primaryFunction } } }",
+            "query { hero { friends { name } friends { __typename } } }",
+            "query { hero { ... on Human { friends { name } } ... on Droid { friends { name: __typename } } } }",
+            r##"query { search(text: "R2") { ... on Starship { length(unit: "m") } ... on Starship { length(unit: "m") } } }"##,
+        ];
+
+        for query in valid {
+            assert_eq!(validate_characters_query(query), Ok(()), "{}", query);
+        }
+    }
+
+    #[test]
+    fn fields_conflict_with_different_fields() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    name
+                    name: __typename
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FieldsConflict {
+                response_key: "name".to_string(),
+                reason: "name and __typename are different fields".to_string(),
+                position: Pos { line: 5, column: 21 },
+            })
+        }
+    }
+
+    #[test]
+    fn fields_conflict_with_different_arguments() {
+        assert_validates! {
+            r##"
+            query {
+                search(text: "R2") {
+                    ... on Starship {
+                        length(unit: "m")
+                    }
+                    ... on Starship {
+                        length(unit: "ft")
+                    }
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FieldsConflict {
+                response_key: "length".to_string(),
+                reason: "they have different arguments".to_string(),
+                position: Pos { line: 8, column: 25 },
+            })
+        }
+    }
+
+    #[test]
+    fn fields_conflict_with_different_shapes() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    ... on Human {
+                        value: homePlanet
+                    }
+                    ... on Droid {
+                        value: friends {
+                            name
+                        }
+                    }
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FieldsConflict {
+                response_key: "value".to_string(),
+                reason: "they return conflicting types String and [Character]".to_string(),
+                position: Pos { line: 8, column: 25 },
+            })
+        }
+    }
+
+    #[test]
+    fn subfields_conflict_through_fragments() {
+        assert_validates! {
+            r##"
+            query {
+                hero {
+                    friends {
+                        name
+                    }
+                    ...friendNames
+                }
+            }
+
+            fragment friendNames on Character {
+                friends {
+                    name: __typename
+                }
+            }
+            "##,
+            CHARACTERS_SCHEMA =>
+            Err(QueryValidationError::FieldsConflict {
+                response_key: "name".to_string(),
+                reason: "name and __typename are different fields".to_string(),
+                position: Pos { line: 13, column: 21 },
+            })
         }
     }
 
+    #[test]
+    fn all_errors_are_collected() {
+        let query = graphql_parser::parse_query(
+            r##"
+            query($unused: Int) {
+                hero {
+                    name {
+                        first
+                    }
+                    ...heroFields
+                    friends {
+                        ...heroFields
+                    }
+                }
+                search(text: "R2") {
+                    ... on Starship {
+                        length(unit: 3)
+                    }
+                }
+            }
+
+            fragment heroFields on Character {
+                friends
+            }
+            "##,
+        ).unwrap();
+        let schema = graphql_parser::parse_schema(CHARACTERS_SCHEMA).unwrap();
+
+        assert_eq!(
+            validate_query_all(&query, json::Map::new(), None, &schema),
+            Err(vec![
+                QueryValidationError::SelectionOnLeafField {
+                    field: "name".to_string(),
+                    field_type: "String!".to_string(),
+                    position: Pos { line: 4, column: 21 },
+                },
+                QueryValidationError::MissingSelectionSet {
+                    field: "friends".to_string(),
+                    field_type: "[Character]".to_string(),
+                    position: Pos { line: 20, column: 17 },
+                },
+                QueryValidationError::InvalidInputValue {
+                    expected_type: "String".to_string(),
+                    value: "3".to_string(),
+                    path: "unit".to_string(),
+                    position: Pos { line: 14, column: 25 },
+                },
+                QueryValidationError::UnusedVariable {
+                    name: "unused".to_string(),
+                    position: Pos { line: 2, column: 19 },
+                },
+            ])
+        );
+
+        assert_eq!(
+            validate_query(&query, json::Map::new(), None, &schema),
+            Err(QueryValidationError::SelectionOnLeafField {
+                field: "name".to_string(),
+                field_type: "String!".to_string(),
+                position: Pos { line: 4, column: 21 },
+            })
+        );
+    }
+
+    #[test]
+    fn all_fragment_cycles_are_collected() {
+        let query = graphql_parser::parse_query(
+            r##"
+            query {
+                hero {
+                    ...nameField
+                    ...friendFields
+                }
+            }
+
+            fragment nameField on Character {
+                ...nameField
+            }
+
+            fragment friendFields on Character {
+                friends {
+                    ...friendFields
+                }
+            }
+            "##,
+        ).unwrap();
+        let schema = graphql_parser::parse_schema(CHARACTERS_SCHEMA).unwrap();
+
+        assert_eq!(
+            validate_query_all(&query, json::Map::new(), None, &schema),
+            Err(vec![
+                QueryValidationError::CyclicFragment {
+                    name: "nameField".to_string(),
+                    position: Pos { line: 10, column: 20 },
+                },
+                QueryValidationError::CyclicFragment {
+                    name: "friendFields".to_string(),
+                    position: Pos { line: 15, column: 24 },
+                },
+            ])
+        );
+    }
+
     #[test]
     fn query_value_to_json_works() {
         use graphql_parser::query::Value;
@@ -597,6 +3269,7 @@ mod tests {
             (Value::Boolean(true), json::Value::Bool(true)),
             (Value::Float(33.4), json!(33.4)),
             (Value::Null, json!(null)),
+            (Value::Enum("HEEL".to_string()), json!("HEEL")),
             (
                 Value::String("Ravelociraptor".to_string()),
                 json!("Ravelociraptor"),
@@ -606,5 +3279,10 @@ mod tests {
         for case in cases {
             assert_eq!(query_value_to_json(&case.0).unwrap(), case.1);
         }
+
+        assert_eq!(
+            query_value_to_json(&Value::Variable("dogName".to_string())),
+            Err(QueryValidationError::Other)
+        );
     }
 }
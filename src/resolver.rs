@@ -1,8 +1,9 @@
 use errors::ResolverError;
 use futures::prelude::*;
-use serde_json as json;
+use response::Resolved;
 
-pub type ResolverFuture = Box<Future<Item = json::Value, Error = Vec<ResolverError>>>;
+/// Resolves to the data for an operation, along with the errors of the fields that could not be resolved. The future fails when the whole operation does.
+pub type ResolverFuture = Box<Future<Item = Resolved, Error = Vec<ResolverError>>>;
 
 pub trait Resolver {
     type Schema;
@@ -4,16 +4,19 @@
 //!
 //! This is achieved with the `on` and `merge` methods on `Response` and judicious usage of Rust's pattern matching and iterators.
 
+use errors::ResolverError;
+use futures::future;
 use futures::prelude::*;
 use serde_json as json;
 use std::collections::HashMap;
 
+/// Implemented by the selection enums. The path fragment of a selected field is its response key, that is its alias if it has one, its name otherwise.
 pub trait PathFragment {
-    fn as_path_fragment(&self) -> &'static str;
+    fn as_path_fragment(&self) -> &str;
 }
 
 impl<'a, T: PathFragment> PathFragment for &'a T {
-    fn as_path_fragment(&self) -> &'static str {
+    fn as_path_fragment(&self) -> &str {
         (*self).as_path_fragment()
     }
 }
@@ -79,11 +82,194 @@ impl<'a, T: PathFragment> PathFragment for &'a T {
 //     }
 // }
 
+/// The response for a single field, keyed by its response key.
 pub enum Response {
-    Async(Box<Future<Item = (&'static str, json::Value), Error = ::errors::ResolverError>>),
+    Async(Box<Future<Item = (String, Resolved), Error = ::errors::ResolverError>>),
     /// Produced by attaching to a dataloader
     // Deferred(::futures::sync::oneshot::Receiver<json::Value>),
-    Immediate((&'static str, json::Value)),
+    Immediate((String, json::Value)),
+}
+
+/// A resolved value, along with the errors raised by the fields that could not be resolved.
+///
+/// Those fields are null in the value. When they are non-null, the null bubbles up to their nearest nullable parent instead, as described in the [spec](https://github.com/facebook/graphql/blob/master/spec/Section%206%20--%20Execution.md#errors-and-non-nullability). The paths of the errors are relative to the value.
+#[derive(Debug, PartialEq)]
+pub struct Resolved {
+    pub value: json::Value,
+    pub errors: Vec<ResolverError>,
+}
+
+impl From<json::Value> for Resolved {
+    fn from(value: json::Value) -> Resolved {
+        Resolved {
+            value,
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// An entry of an `ObjectResponse`, once resolved.
+struct Entry {
+    /// `None` for fragments, whose fields are merged into the object.
+    response_key: Option<String>,
+    non_null: Option<&'static str>,
+    resolved: Resolved,
+}
+
+/// Gathers the responses to the fields of an object. This is what the generated responders use, so one failing field does not fail the whole response.
+///
+/// The errors of a field are recorded with the response key of the field prepended to their path, and the field is null. When a non-null field is null, the whole object is null.
+pub struct ObjectResponse {
+    entries: Vec<Box<Future<Item = Entry, Error = ResolverError>>>,
+}
+
+impl ObjectResponse {
+    pub fn new() -> ObjectResponse {
+        ObjectResponse {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds the response to a field. `non_null` is the name of the field, as `Type.field`, if its type is non-null.
+    pub fn field(
+        &mut self,
+        response_key: String,
+        non_null: Option<&'static str>,
+        response: Response,
+    ) {
+        let entry: Box<Future<Item = Entry, Error = ResolverError>> = match response {
+            Response::Immediate((_, value)) => Box::new(future::ok(Entry {
+                response_key: Some(response_key),
+                non_null,
+                resolved: value.into(),
+            })),
+            Response::Async(fut) => Box::new(fut.then(move |result| {
+                let mut resolved = match result {
+                    Ok((_, resolved)) => resolved,
+                    Err(error) => Resolved {
+                        value: json::Value::Null,
+                        errors: vec![error],
+                    },
+                };
+
+                for error in resolved.errors.iter_mut() {
+                    error.prepend_path(response_key.as_str());
+                }
+
+                Ok(Entry {
+                    response_key: Some(response_key),
+                    non_null,
+                    resolved,
+                })
+            })),
+        };
+
+        self.entries.push(entry);
+    }
+
+    /// Adds the response to a fragment, whose fields are merged into the object.
+    pub fn fragment(&mut self, response: Response) {
+        let entry: Box<Future<Item = Entry, Error = ResolverError>> = match response {
+            Response::Immediate((_, value)) => Box::new(future::ok(Entry {
+                response_key: None,
+                non_null: None,
+                resolved: value.into(),
+            })),
+            Response::Async(fut) => Box::new(fut.then(|result| {
+                Ok(Entry {
+                    response_key: None,
+                    non_null: None,
+                    resolved: match result {
+                        Ok((_, resolved)) => resolved,
+                        // The fields of the fragment are missing, but the object is not null.
+                        Err(error) => Resolved {
+                            value: json::Value::Object(json::Map::new()),
+                            errors: vec![error],
+                        },
+                    },
+                })
+            })),
+        };
+
+        self.entries.push(entry);
+    }
+
+    pub fn resolve(self) -> Box<Future<Item = Resolved, Error = ResolverError>> {
+        Box::new(future::join_all(self.entries).map(|entries| {
+            let mut object = json::Map::with_capacity(entries.len());
+            let mut errors = Vec::new();
+            let mut is_null = false;
+
+            for entry in entries {
+                let Entry {
+                    response_key,
+                    non_null,
+                    resolved,
+                } = entry;
+
+                match (response_key, resolved.value) {
+                    (Some(response_key), json::Value::Null) => {
+                        if let Some(field) = non_null {
+                            is_null = true;
+
+                            // Nulls that do not come from an error still have to be reported.
+                            if resolved.errors.is_empty() {
+                                errors.push(
+                                    ResolverError::new(format!(
+                                        "Null value for non-null field {}",
+                                        field
+                                    ))
+                                    .with_path(vec![response_key.as_str().into()]),
+                                );
+                            }
+                        }
+                        object.insert(response_key, json::Value::Null);
+                    }
+                    (Some(response_key), value) => {
+                        object.insert(response_key, value);
+                    }
+                    (None, json::Value::Object(fields)) => object.extend(fields),
+                    // The null already bubbled up to the fragment.
+                    (None, json::Value::Null) => is_null = true,
+                    (None, _) => (),
+                }
+
+                errors.extend(resolved.errors);
+            }
+
+            Resolved {
+                value: if is_null {
+                    json::Value::Null
+                } else {
+                    json::Value::Object(object)
+                },
+                errors,
+            }
+        }))
+    }
+}
+
+/// The responder for the `__typename` meta-field, which every object, interface and union selection can contain. The generated responders answer it with the concrete type name, so resolvers never have to.
+#[derive(Debug, PartialEq, Default)]
+pub struct TypenameResponder {
+    /// The alias of the field in the query, if any.
+    pub alias: Option<String>,
+}
+
+impl TypenameResponder {
+    pub fn response_key(&self) -> &str {
+        self.alias
+            .as_ref()
+            .map(|alias| alias.as_str())
+            .unwrap_or("__typename")
+    }
+
+    pub fn with(&self, typename: &str) -> Response {
+        Response::Immediate((
+            self.response_key().to_string(),
+            json::Value::String(typename.to_string()),
+        ))
+    }
 }
 
 // impl IntoFuture for ResponseValue {
@@ -220,6 +406,66 @@ mod tests {
     #[derive(Debug, PartialEq)]
     struct Error;
 
+    fn failure(message: &str) -> Response {
+        Response::Async(Box::new(::futures::future::err(
+            ::errors::ResolverError::new(message),
+        )))
+    }
+
+    fn immediate(response_key: &str, value: json::Value) -> Response {
+        Response::Immediate((response_key.to_string(), value))
+    }
+
+    #[test]
+    fn object_response_with_failing_fields() {
+        let mut object = ObjectResponse::new();
+        object.field(
+            "name".to_string(),
+            Some("Dog.name"),
+            immediate("name", json!("Rex")),
+        );
+        object.field("age".to_string(), None, failure("Unknown age"));
+        object.fragment(immediate("", json!({ "fluffiness": 9 })));
+
+        assert_eq!(
+            object.resolve().wait().unwrap(),
+            Resolved {
+                value: json!({ "name": "Rex", "age": null, "fluffiness": 9 }),
+                errors: vec![
+                    ::errors::ResolverError::new("Unknown age").with_path(vec!["age".into()])
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn object_response_with_null_non_null_fields() {
+        let mut object = ObjectResponse::new();
+        object.field(
+            "name".to_string(),
+            Some("Dog.name"),
+            failure("Unknown name"),
+        );
+        object.field(
+            "owner".to_string(),
+            Some("Dog.owner"),
+            immediate("owner", json::Value::Null),
+        );
+        object.field("age".to_string(), None, immediate("age", json!(3)));
+
+        assert_eq!(
+            object.resolve().wait().unwrap(),
+            Resolved {
+                value: json::Value::Null,
+                errors: vec![
+                    ::errors::ResolverError::new("Unknown name").with_path(vec!["name".into()]),
+                    ::errors::ResolverError::new("Null value for non-null field Dog.owner")
+                        .with_path(vec!["owner".into()]),
+                ],
+            }
+        );
+    }
+
     fn resolve_query_root(_res: Response) -> Result<json::Value, Error> {
         Ok(json!({ "weight": 4 }))
     }
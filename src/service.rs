@@ -0,0 +1,156 @@
+//! The service layer sits between a transport, like the `standalone-server` crate, and the resolvers. It takes requests as sent by clients and produces the responses to send back.
+
+use coercion::{CoerceQueryDocument, Pruning};
+use errors::GqlError;
+use execution::{execute_with_pruning, ExecutionResult, Request};
+use futures::prelude::*;
+use graphql_parser;
+use resolver::Resolver;
+use std::sync::Arc;
+
+pub type ServiceFuture<Error> = Box<Future<Item = ExecutionResult, Error = Error>>;
+
+pub trait GqlService {
+    /// Errors that prevent the service from producing a response. Transports report their own failures (for example a malformed request body) as `GqlError`s, hence the `From` bound.
+    type Error: From<GqlError>;
+
+    fn handle_request(&self, request: Request) -> ServiceFuture<Self::Error>;
+
+    /// Turns an error into the response sent to the client. This is the place to log errors, or hide their details from clients.
+    fn handle_errors(&self, error: Self::Error) -> ExecutionResult;
+}
+
+/// Serves a `Resolver`, validating requests against the schema it was derived from.
+///
+/// The derive generates a `THE_SCHEMA` constant for that purpose: `ResolverService::new(schema::THE_SCHEMA, resolver)`.
+pub struct ResolverService<R> {
+    schema: Arc<graphql_parser::schema::Document>,
+    resolver: R,
+    pruning: Pruning,
+}
+
+impl<R> ResolverService<R> {
+    pub fn new(
+        schema: &str,
+        resolver: R,
+    ) -> Result<ResolverService<R>, graphql_parser::schema::ParseError> {
+        Ok(ResolverService {
+            schema: Arc::new(graphql_parser::parse_schema(schema)?),
+            resolver,
+            pruning: Pruning::default(),
+        })
+    }
+
+    /// Sets how strictly responses are pruned to the shape of the query. The default is `Pruning::Lenient`.
+    pub fn with_pruning(mut self, pruning: Pruning) -> ResolverService<R> {
+        self.pruning = pruning;
+        self
+    }
+}
+
+impl<R> GqlService for ResolverService<R>
+where
+    R: Resolver,
+    R::Schema: CoerceQueryDocument,
+    R::Responder: Default,
+{
+    type Error = GqlError;
+
+    fn handle_request(&self, request: Request) -> ServiceFuture<GqlError> {
+        Box::new(
+            execute_with_pruning(&self.schema, request, &self.resolver, self.pruning)
+                .map_err(|()| GqlError::InternalError),
+        )
+    }
+
+    fn handle_errors(&self, error: GqlError) -> ExecutionResult {
+        ExecutionResult::from_error(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coercion::CoercionError;
+    use futures::future;
+    use graphql_parser::query::*;
+    use query_validation::ValidationContext;
+    use resolver::ResolverFuture;
+    use serde_json as json;
+
+    const SCHEMA: &str = r##"
+    type Query {
+        answer: Int
+    }
+
+    schema {
+        query: Query
+    }
+    "##;
+
+    #[derive(Debug, PartialEq)]
+    struct Operation;
+
+    impl CoerceQueryDocument for Operation {
+        fn coerce(
+            _document: &Document,
+            _context: &ValidationContext,
+        ) -> Result<Vec<Operation>, CoercionError> {
+            Ok(vec![Operation])
+        }
+    }
+
+    struct AnswerResolver;
+
+    impl Resolver for AnswerResolver {
+        type Schema = Operation;
+        type Responder = ();
+
+        fn resolve(&self, _request: Operation, _responder: ()) -> ResolverFuture {
+            Box::new(future::ok(json!({ "answer": 42 }).into()))
+        }
+    }
+
+    #[test]
+    fn resolver_service_handles_requests() {
+        let service = ResolverService::new(SCHEMA, AnswerResolver).unwrap();
+        let result = service
+            .handle_request(Request::new("query { answer }"))
+            .wait()
+            .unwrap();
+
+        assert_eq!(
+            json::to_value(&result).unwrap(),
+            json!({ "data": { "answer": 42 } })
+        );
+    }
+
+    #[test]
+    fn strict_resolver_service_rejects_unrequested_fields() {
+        let service = ResolverService::new(SCHEMA, AnswerResolver)
+            .unwrap()
+            .with_pruning(Pruning::Strict);
+        let result = service
+            .handle_request(Request::new("query { __typename }"))
+            .wait()
+            .unwrap();
+
+        assert_eq!(
+            json::to_value(&result).unwrap(),
+            json!({
+                "data": { "__typename": "Query" },
+                "errors": [{ "message": "Unexpected field answer on type Query", "path": ["answer"] }],
+            })
+        );
+    }
+
+    #[test]
+    fn resolver_service_handles_errors() {
+        let service = ResolverService::new(SCHEMA, AnswerResolver).unwrap();
+
+        assert_eq!(
+            json::to_value(&service.handle_errors(GqlError::InvalidRequest)).unwrap(),
+            json!({ "errors": [{ "message": "Invalid request" }] })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use coercion::ScalarValue;
 use serde_json;
 
 pub trait IntoJson {
@@ -35,3 +36,9 @@ impl<T: IntoJson> IntoJson for Option<T> {
         }
     }
 }
+
+impl<T: ScalarValue> IntoJson for T {
+    fn into_json(self) -> serde_json::Value {
+        self.to_json()
+    }
+}
@@ -1,19 +1,15 @@
 extern crate futures;
 extern crate hyper;
-#[macro_use]
+#[cfg_attr(test, macro_use)]
 extern crate serde_json;
 extern crate tokio_gql;
 
 use futures::prelude::*;
 use hyper::server::{NewService, Service};
 use serde_json as json;
-use std::fmt::Debug;
 use std::sync::Arc;
-use tokio_gql::coercion::CoerceQueryDocument;
 use tokio_gql::errors::GqlError;
-use tokio_gql::graphql_parser;
-use tokio_gql::query_validation::ValidationContext;
-use tokio_gql::response::Response;
+use tokio_gql::execution::{ExecutionResult, Request};
 use tokio_gql::service::GqlService;
 
 struct ServerWrapper<Server>(Arc<Server>);
@@ -32,11 +28,9 @@ where
     }
 }
 
-impl<Schema, Error, Resolver> Service for ServerWrapper<StandaloneServer<Schema, Error, Resolver>>
+impl<S> Service for ServerWrapper<StandaloneServer<S>>
 where
-    Error: Debug + PartialEq + 'static,
-    Schema: Debug + CoerceQueryDocument + 'static,
-    Resolver: GqlService<Schema = Schema, Error = Error> + 'static,
+    S: GqlService + 'static,
 {
     type Request = hyper::Request<hyper::Body>;
     type Response = hyper::Response<hyper::Body>;
@@ -45,21 +39,25 @@ where
 
     fn call(&self, req: Self::Request) -> Self::Future {
         let cloned = self.0.clone();
+        let raw_query = req.headers()
+            .get::<hyper::header::ContentType>()
+            .map(|content_type| {
+                content_type.type_() == "application" && content_type.subtype() == "graphql"
+            })
+            .unwrap_or(false);
 
         match (req.uri().path(), req.method()) {
             ("/graphql", hyper::Method::Get) => Box::new(playground().into_future()),
             ("/graphql", hyper::Method::Post) => Box::new(
-                body_to_response(self.0.clone(), req.body())
+                body_to_response(self.0.clone(), raw_query, req.body())
+                    .or_else(move |err| Ok(cloned.service.handle_errors(err)))
                     .and_then(|body| {
                         let mut res = hyper::Response::new();
                         res.set_body(json::to_string(&body).expect("the response is valid json"));
-                        Ok(res)
-                    })
-                    .or_else(move |err| {
-                        let mut res = hyper::Response::new();
-                        let resolver: &Resolver = &cloned.resolver;
-                        let body_string = json::to_string(&resolver.handle_errors(err)).unwrap();
-                        res.set_body(body_string);
+                        {
+                            let headers = res.headers_mut();
+                            headers.set_raw("Content-Type", "application/json");
+                        }
                         Ok(res)
                     }),
             ),
@@ -78,36 +76,29 @@ fn redirect() -> Result<hyper::Response, hyper::Error> {
     Ok(res)
 }
 
-fn body_to_response<
-    Schema: Debug + CoerceQueryDocument + 'static,
-    Error: Debug + PartialEq + 'static,
-    Resolver: GqlService<Schema = Schema, Error = Error> + 'static,
->(
-    server: Arc<StandaloneServer<Schema, Error, Resolver>>,
+fn body_to_response<S: GqlService + 'static>(
+    server: Arc<StandaloneServer<S>>,
+    raw_query: bool,
     body: hyper::Body,
-) -> impl Future<Item = json::Value, Error = GqlError<Error>> {
-    body.map_err(|_| GqlError::InternalError)
+) -> impl Future<Item = ExecutionResult, Error = S::Error> {
+    body.map_err(|_| GqlError::InternalError.into())
         .fold(Vec::new(), |mut acc, item| {
             acc.extend(item);
-            Ok(acc) as Result<Vec<u8>, GqlError<Error>>
-        })
-        .and_then(|req_body| String::from_utf8(req_body).map_err(|_| GqlError::InvalidRequest))
-        .and_then(|request_string| {
-            let parsed_query = graphql_parser::parse_query(&request_string)?;
-            let parsed_variables = json!({});
-            let parsed_variables = json::Map::new();
-            let validation_context = ValidationContext::new(parsed_variables);
-            let query =
-                <Schema as CoerceQueryDocument>::coerce(&parsed_query, &validation_context)?;
-            Ok(query)
+            Ok(acc) as Result<Vec<u8>, S::Error>
         })
-        .and_then(move |query| {
-            server
-                .resolver
-                .resolve(query, Response::new())
-                .map_err(|err| GqlError::ResolverError(err))
-        })
-        .map(|_| json!({}))
+        .and_then(move |req_body| parse_request(raw_query, req_body).map_err(S::Error::from))
+        .and_then(move |request| server.service.handle_request(request))
+}
+
+/// `application/graphql` bodies are the query itself. Anything else is expected to be a JSON object with `query`, `variables` and `operationName` keys.
+fn parse_request(raw_query: bool, body: Vec<u8>) -> Result<Request, GqlError> {
+    if raw_query {
+        String::from_utf8(body)
+            .map(Request::new)
+            .map_err(|_| GqlError::InvalidRequest)
+    } else {
+        json::from_slice(&body).map_err(|_| GqlError::InvalidRequest)
+    }
 }
 
 fn playground() -> Result<hyper::Response, hyper::Error> {
@@ -121,23 +112,14 @@ fn playground() -> Result<hyper::Response, hyper::Error> {
     Ok(res)
 }
 
-pub struct StandaloneServer<Schema, Error, Resolver>
-where
-    Schema: CoerceQueryDocument,
-    Error: Debug + PartialEq,
-    Resolver: GqlService<Schema = Schema, Error = Error>,
-{
-    resolver: Resolver,
+/// Serves a `GqlService` over HTTP. Queries are sent to `/graphql` with POST requests, and GET requests to the same path serve the GraphQL playground.
+pub struct StandaloneServer<S: GqlService> {
+    service: S,
 }
 
-impl<Schema, Error, Resolver> StandaloneServer<Schema, Error, Resolver>
-where
-    Schema: Debug + CoerceQueryDocument + 'static,
-    Error: Debug + PartialEq + 'static,
-    Resolver: GqlService<Schema = Schema, Error = Error> + 'static,
-{
-    pub fn new(resolver: Resolver) -> Self {
-        StandaloneServer { resolver }
+impl<S: GqlService + 'static> StandaloneServer<S> {
+    pub fn new(service: S) -> Self {
+        StandaloneServer { service }
     }
 
     pub fn start(self) -> Result<(), ()> {
@@ -152,3 +134,188 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use tokio_gql::service::ServiceFuture;
+
+    /// Answers every request with the request itself, so tests can check how it was parsed.
+    struct EchoService;
+
+    impl GqlService for EchoService {
+        type Error = GqlError;
+
+        fn handle_request(&self, request: Request) -> ServiceFuture<GqlError> {
+            Box::new(future::ok(ExecutionResult {
+                data: Some(json!({
+                    "query": request.query,
+                    "variables": request.variables,
+                    "operationName": request.operation_name,
+                })),
+                errors: Vec::new(),
+            }))
+        }
+
+        fn handle_errors(&self, error: GqlError) -> ExecutionResult {
+            ExecutionResult::from_error(error)
+        }
+    }
+
+    fn call(
+        method: hyper::Method,
+        path: &str,
+        content_type: Option<&str>,
+        body: &str,
+    ) -> hyper::Response {
+        let mut request = hyper::Request::new(method, path.parse().unwrap());
+        if let Some(content_type) = content_type {
+            request
+                .headers_mut()
+                .set_raw("Content-Type", content_type.to_string());
+        }
+        request.set_body(body.to_string());
+
+        ServerWrapper(Arc::new(StandaloneServer::new(EchoService)))
+            .call(request)
+            .wait()
+            .unwrap()
+    }
+
+    fn json_body(response: hyper::Response) -> json::Value {
+        let body = response.body().concat2().wait().unwrap();
+        json::from_slice(&body).unwrap()
+    }
+
+    #[test]
+    fn parse_raw_query() {
+        assert_eq!(
+            parse_request(true, b"{ hero { name } }".to_vec()).unwrap(),
+            Request::new("{ hero { name } }")
+        );
+    }
+
+    #[test]
+    fn parse_json_request() {
+        let body = r##"{
+            "query": "query Hero($episode: Episode) { hero(episode: $episode) { name } }",
+            "variables": { "episode": "JEDI" },
+            "operationName": "Hero"
+        }"##;
+        let mut variables = json::Map::new();
+        variables.insert("episode".to_string(), json!("JEDI"));
+
+        assert_eq!(
+            parse_request(false, body.as_bytes().to_vec()).unwrap(),
+            Request {
+                query: "query Hero($episode: Episode) { hero(episode: $episode) { name } }"
+                    .to_string(),
+                variables: Some(variables),
+                operation_name: Some("Hero".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_json_request_without_variables() {
+        assert_eq!(
+            parse_request(false, br##"{ "query": "{ hero { name } }" }"##.to_vec()).unwrap(),
+            Request::new("{ hero { name } }")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_requests() {
+        let invalid = vec![
+            (false, b"{ hero { name } }".to_vec()),
+            (false, br##"{ "variables": {} }"##.to_vec()),
+            (false, br##"{ "query": 3 }"##.to_vec()),
+            (false, Vec::new()),
+            (true, vec![0xff, 0xfe]),
+        ];
+
+        for (raw_query, body) in invalid {
+            match parse_request(raw_query, body.clone()) {
+                Err(GqlError::InvalidRequest) => (),
+                other => panic!("{:?} parsed as {:?}", body, other),
+            }
+        }
+    }
+
+    #[test]
+    fn post_json_request() {
+        let response = call(
+            hyper::Method::Post,
+            "/graphql",
+            Some("application/json"),
+            r##"{ "query": "{ hero { name } }", "operationName": "Hero" }"##,
+        );
+
+        assert_eq!(response.status(), hyper::StatusCode::Ok);
+        assert_eq!(
+            json_body(response),
+            json!({
+                "data": {
+                    "query": "{ hero { name } }",
+                    "variables": null,
+                    "operationName": "Hero",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn post_graphql_request() {
+        let response = call(
+            hyper::Method::Post,
+            "/graphql",
+            Some("application/graphql"),
+            "{ hero { name } }",
+        );
+
+        assert_eq!(
+            json_body(response),
+            json!({
+                "data": {
+                    "query": "{ hero { name } }",
+                    "variables": null,
+                    "operationName": null,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_request_body() {
+        let response = call(hyper::Method::Post, "/graphql", None, "{ hero { name } }");
+
+        assert_eq!(
+            response.headers().get_raw("Content-Type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            json_body(response),
+            json!({ "errors": [{ "message": "Invalid request" }] })
+        );
+    }
+
+    #[test]
+    fn get_serves_the_playground() {
+        let response = call(hyper::Method::Get, "/graphql", None, "");
+
+        assert_eq!(response.status(), hyper::StatusCode::Ok);
+        assert_eq!(
+            response.headers().get_raw("Content-Type").unwrap(),
+            "text/html"
+        );
+    }
+
+    #[test]
+    fn other_paths_redirect() {
+        let response = call(hyper::Method::Get, "/", None, "");
+
+        assert_eq!(response.status(), hyper::StatusCode::SeeOther);
+        assert_eq!(response.headers().get_raw("Location").unwrap(), "/graphql");
+    }
+}
@@ -36,16 +36,33 @@ fn test_coercion<OperationType: CoerceQueryDocument + ::std::fmt::Debug + Partia
 fn test_coercion_with_context<
     OperationType: CoerceQueryDocument + ::std::fmt::Debug + PartialEq,
 >(
-    context: tokio_gql::query_validation::ValidationContext,
+    mut context: tokio_gql::query_validation::ValidationContext,
     query: &str,
     expected_result: Result<Vec<OperationType>, CoercionError>,
 ) {
     let query = parse_query(query).unwrap();
+    for definition in query.definitions.iter() {
+        if let Definition::Fragment(fragment) = definition {
+            context.push_fragment_definition(fragment);
+        }
+    }
     let fields = OperationType::coerce(&query, &context);
 
     assert_eq!(fields, expected_result,)
 }
 
+fn test_coercion_error<OperationType: CoerceQueryDocument + ::std::fmt::Debug>(
+    query: &str,
+    expected_message: &str,
+) -> CoercionError {
+    let context = tokio_gql::query_validation::ValidationContext::new(serde_json::Map::new());
+    let query = parse_query(query).unwrap();
+    let error = OperationType::coerce(&query, &context).unwrap_err();
+
+    assert_eq!(error.to_string(), expected_message);
+    error
+}
+
 #[test]
 fn query_coercion_works() {
     let query = r##"
@@ -67,6 +84,21 @@ fn query_coercion_works() {
     test_coercion::<Operation>(query, expected);
 }
 
+#[test]
+fn shorthand_query_coercion() {
+    let query = r##"
+    {
+        lastName
+    }
+    "##;
+    let expected = Ok(vec![Operation::Query {
+        selection: vec![User::LastName {
+            respond: Default::default(),
+        }],
+    }]);
+    test_coercion::<Operation>(query, expected);
+}
+
 #[test]
 fn basic_argument_coercion() {
     let query = r##"
@@ -117,15 +149,38 @@ fn optional_argument_coercion_some() {
     );
 }
 
-/// We do not consider this as an error because that should be caught at the validation step.
 #[test]
 fn wrong_argument_name_coercion() {
-    test_coercion::<Operation>(
+    test_coercion_error::<Operation>(
         r##"
     query {
         sayHello(name: 33)
     }
     "##,
+        "Invalid value for argument name of sayHello: expected String, found 33",
+    );
+}
+
+#[test]
+fn wrong_argument_type_coercion() {
+    test_coercion_error::<Operation>(
+        r##"
+        query {
+            sayHello(age: "meow")
+        }
+        "##,
+        "Invalid value for argument age of sayHello: unknown argument",
+    );
+}
+
+#[test]
+fn absent_optional_argument_coercion() {
+    test_coercion::<Operation>(
+        r##"
+        query {
+            sayHello
+        }
+        "##,
         Ok(vec![Operation::Query {
             selection: vec![User::SayHello {
                 respond: Default::default(),
@@ -136,15 +191,69 @@ fn wrong_argument_name_coercion() {
 }
 
 #[test]
-fn wrong_argument_type_coercion() {
-    test_coercion::<Operation>(
+fn wrong_optional_variable_coercion() {
+    let mut variables = serde_json::Map::new();
+    variables.insert("greeted".to_string(), json!(33));
+    let context = tokio_gql::query_validation::ValidationContext::new(variables);
+    let query = parse_query(
+        r##"
+        query Greet($greeted: String) {
+            sayHello(name: $greeted)
+        }
+        "##,
+    ).unwrap();
+
+    assert_eq!(
+        Operation::coerce(&query, &context).unwrap_err().to_string(),
+        "Invalid value for argument name of sayHello: expected String, found 33"
+    );
+}
+
+#[test]
+fn wrong_optional_input_object_field_coercion() {
+    test_coercion_error::<Operation>(
         r##"
         query {
-            sayHello(age: "meow")
+            petDog(dog: { name: "Rex", weight: 12, has_chip: "yes" })
         }
         "##,
-        Err(CoercionError),
-    )
+        "Invalid value for argument dog of petDog: expected Boolean for field has_chip of Dog, found \"yes\"",
+    );
+}
+
+#[test]
+fn invalid_argument_coercion() {
+    let error = test_coercion_error::<Operation>(
+        r##"
+        query {
+            double(num: "four")
+        }
+        "##,
+        "Invalid value for argument num of double: expected Int!, found \"four\"",
+    );
+
+    assert_eq!(error.argument(), Some("num"));
+    assert_eq!(error.expected_type(), Some("Int!"));
+    assert_eq!(error.value(), Some(&json!("four")));
+    assert_eq!(error.path().iter().collect::<Vec<_>>(), vec!["double"]);
+}
+
+#[test]
+fn invalid_input_object_field_coercion() {
+    let error = test_coercion_error::<star_wars::Operation>(
+        r##"
+        mutation {
+            review: createReview(episode: JEDI, review: { commentary: "Great!" }) {
+                stars
+            }
+        }
+        "##,
+        "Invalid value for argument review of review: expected Int! for field stars of ReviewInput, found no value",
+    );
+
+    assert_eq!(error.argument(), Some("review"));
+    assert_eq!(error.expected_type(), Some("Int!"));
+    assert_eq!(error.value(), None);
 }
 
 #[test]
@@ -388,33 +497,25 @@ fn union_coercion() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Search {
-                    respond: Default::default(),
-                    text: Some("Jar Jar Binks".to_string()),
-                    selection: vec![
-                        star_wars::SearchResult::OnHuman(vec![
-                            star_wars::Human::Name {
-                                respond: Default::default(),
-                            },
-                            star_wars::Human::HomePlanet {
-                                respond: Default::default(),
-                            },
-                        ]),
-                        star_wars::SearchResult::OnDroid(vec![star_wars::Droid::Name {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Search {
+                respond: Default::default(),
+                text: Some("Jar Jar Binks".to_string()),
+                selection: vec![
+                    star_wars::SearchResult::OnHuman(vec![
+                        star_wars::Human::Name {
                             respond: Default::default(),
-                        }]),
-                    ],
-                }],
-            },
-            star_wars::Operation::Mutation {
-                selection: Vec::new(),
-            },
-            star_wars::Operation::Subscription {
-                selection: Vec::new(),
-            },
-        ]),
+                        },
+                        star_wars::Human::HomePlanet {
+                            respond: Default::default(),
+                        },
+                    ]),
+                    star_wars::SearchResult::OnDroid(vec![star_wars::Droid::Name {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
     );
 }
 
@@ -429,23 +530,15 @@ fn enum_argument_coercion() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Hero {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: Some(star_wars::Episode::Jedi),
+                selection: vec![star_wars::Character::Name {
                     respond: Default::default(),
-                    episode: Some(star_wars::Episode::Jedi),
-                    selection: vec![star_wars::Character::Name {
-                        respond: Default::default(),
-                    }],
                 }],
-            },
-            star_wars::Operation::Mutation {
-                selection: Vec::new(),
-            },
-            star_wars::Operation::Subscription {
-                selection: Vec::new(),
-            },
-        ]),
+            }],
+        }]),
     );
 }
 
@@ -460,24 +553,16 @@ fn default_values() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Starship {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Starship {
+                respond: Default::default(),
+                id: "42".to_string(),
+                selection: vec![star_wars::Starship::Length {
                     respond: Default::default(),
-                    id: "42".to_string(),
-                    selection: vec![star_wars::Starship::Length {
-                        respond: Default::default(),
-                        unit: Some(star_wars::LengthUnit::Meter),
-                    }],
+                    unit: Some(star_wars::LengthUnit::Meter),
                 }],
-            },
-            star_wars::Operation::Mutation {
-                selection: Vec::new(),
-            },
-            star_wars::Operation::Subscription {
-                selection: Vec::new(),
-            },
-        ]),
+            }],
+        }]),
     )
 }
 
@@ -499,26 +584,57 @@ fn enum_variable() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Hero {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: Some(star_wars::Episode::Jedi),
+                selection: vec![star_wars::Character::Name {
                     respond: Default::default(),
-                    episode: Some(star_wars::Episode::Jedi),
-                    selection: vec![star_wars::Character::Name {
-                        respond: Default::default(),
-                    }],
                 }],
-            },
-            star_wars::Operation::Mutation {
-                selection: Vec::new(),
-            },
-            star_wars::Operation::Subscription {
-                selection: Vec::new(),
-            },
-        ]),
+            }],
+        }]),
     )
 }
 
+#[test]
+fn enum_variables_and_default_values() {
+    let schema = graphql_parser::parse_schema(include_str!("star_wars_schema.graphql")).unwrap();
+    let query = parse_query(
+        r##"
+        query Query($episode: Episode!, $unit: LengthUnit = FOOT) {
+            hero(episode: $episode) {
+                ... on Human {
+                    height(unit: $unit)
+                }
+            }
+        }
+        "##,
+    ).unwrap();
+    let variables = if let serde_json::Value::Object(map) = json!({ "episode": "EMPIRE" }) {
+        map
+    } else {
+        panic!()
+    };
+    let context =
+        tokio_gql::query_validation::validate_query(&query, variables, None, &schema).unwrap();
+
+    assert_eq!(
+        star_wars::Operation::coerce(&query, &context),
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: Some(star_wars::Episode::Empire),
+                selection: vec![star_wars::Character::OnHuman(vec![
+                    star_wars::Human::Height {
+                        respond: Default::default(),
+                        unit: Some(star_wars::LengthUnit::Foot),
+                    },
+                ])],
+            }],
+        }]),
+    );
+}
+
 #[test]
 fn string_variable() {
     let variables = if let serde_json::Value::Object(map) =
@@ -538,19 +654,15 @@ fn string_variable() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Starship {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Starship {
+                respond: Default::default(),
+                id: "Millenium Falcon!!!".to_string(),
+                selection: vec![star_wars::Starship::Name {
                     respond: Default::default(),
-                    id: "Millenium Falcon!!!".to_string(),
-                    selection: vec![star_wars::Starship::Name {
-                        respond: Default::default(),
-                    }],
                 }],
-            },
-            star_wars::Operation::Mutation { selection: vec![] },
-            star_wars::Operation::Subscription { selection: vec![] },
-        ]),
+            }],
+        }]),
     )
 }
 
@@ -589,9 +701,9 @@ fn input_object_variable() {
 #[test]
 fn missing_variables() {
     let variables = if let serde_json::Value::Object(map) = json!({
-            "email_index": null,
-            "my_number": 43,
-        }) {
+        "email_index": null,
+        "my_number": 43,
+    }) {
         map
     } else {
         panic!()
@@ -672,42 +784,481 @@ fn interface_with_field_and_spread_selection() {
             }
         }
         "##,
-        Ok(vec![
-            star_wars::Operation::Query {
-                selection: vec![star_wars::Query::Character {
-                    respond: Default::default(),
-                    id: "yoda".to_string(),
-                    selection: vec![
-                        star_wars::Character::Id {
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Character {
+                respond: Default::default(),
+                id: "yoda".to_string(),
+                selection: vec![
+                    star_wars::Character::Id {
+                        respond: Default::default(),
+                    },
+                    star_wars::Character::Name {
+                        respond: Default::default(),
+                    },
+                    star_wars::Character::AppearsIn {
+                        respond: Default::default(),
+                    },
+                    star_wars::Character::OnHuman(vec![
+                        star_wars::Human::Height {
                             respond: Default::default(),
+                            unit: Some(star_wars::LengthUnit::Meter),
                         },
-                        star_wars::Character::Name {
+                        star_wars::Human::HomePlanet {
                             respond: Default::default(),
                         },
-                        star_wars::Character::AppearsIn {
+                    ]),
+                    star_wars::Character::OnDroid(vec![star_wars::Droid::PrimaryFunction {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn aliased_fields_coercion() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            empireHero: hero(episode: EMPIRE) {
+                name
+            }
+            jediHero: hero(episode: JEDI) {
+                callsign: name
+            }
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![
+                star_wars::Query::Hero {
+                    respond: star_wars::QueryHeroResponder {
+                        alias: Some("empireHero".to_string()),
+                    },
+                    episode: Some(star_wars::Episode::Empire),
+                    selection: vec![star_wars::Character::Name {
+                        respond: Default::default(),
+                    }],
+                },
+                star_wars::Query::Hero {
+                    respond: star_wars::QueryHeroResponder {
+                        alias: Some("jediHero".to_string()),
+                    },
+                    episode: Some(star_wars::Episode::Jedi),
+                    selection: vec![star_wars::Character::Name {
+                        respond: star_wars::CharacterNameResponder {
+                            alias: Some("callsign".to_string()),
+                        },
+                    }],
+                },
+            ],
+        }]),
+    );
+}
+
+#[test]
+fn named_fragment_on_interface() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            hero(episode: EMPIRE) {
+                ...characterFields
+            }
+        }
+
+        fragment characterFields on Character {
+            name
+            ...humanFields
+        }
+
+        fragment humanFields on Human {
+            homePlanet
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: Some(star_wars::Episode::Empire),
+                selection: vec![
+                    star_wars::Character::Name {
+                        respond: Default::default(),
+                    },
+                    star_wars::Character::OnHuman(vec![star_wars::Human::HomePlanet {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn named_fragment_on_union() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            search(text: "Falcon") {
+                ...searchResultFields
+            }
+        }
+
+        fragment searchResultFields on SearchResult {
+            ...on Starship {
+                name
+            }
+            ...droidFields
+        }
+
+        fragment droidFields on Droid {
+            primaryFunction
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Search {
+                respond: Default::default(),
+                text: Some("Falcon".to_string()),
+                selection: vec![
+                    star_wars::SearchResult::OnStarship(vec![star_wars::Starship::Name {
+                        respond: Default::default(),
+                    }]),
+                    star_wars::SearchResult::OnDroid(vec![star_wars::Droid::PrimaryFunction {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn interface_fragment_on_union() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            search(text: "Luke") {
+                ...on Character {
+                    name
+                }
+            }
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Search {
+                respond: Default::default(),
+                text: Some("Luke".to_string()),
+                selection: vec![
+                    star_wars::SearchResult::OnHuman(vec![star_wars::Human::Name {
+                        respond: Default::default(),
+                    }]),
+                    star_wars::SearchResult::OnDroid(vec![star_wars::Droid::Name {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn union_fragment_on_interface() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            hero(episode: JEDI) {
+                ...results
+            }
+        }
+
+        fragment results on SearchResult {
+            __typename
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: Some(star_wars::Episode::Jedi),
+                selection: vec![
+                    star_wars::Character::OnHuman(vec![star_wars::Human::__Typename {
+                        respond: Default::default(),
+                    }]),
+                    star_wars::Character::OnDroid(vec![star_wars::Droid::__Typename {
+                        respond: Default::default(),
+                    }]),
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn absent_optional_enum_argument_coercion() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            hero {
+                __typename
+            }
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Hero {
+                respond: Default::default(),
+                episode: None,
+                selection: vec![star_wars::Character::__Typename {
+                    respond: Default::default(),
+                }],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn fragment_on_unrelated_type_coercion() {
+    use self::star_wars;
+    test_coercion_error::<star_wars::Operation>(
+        r##"
+        query {
+            hero(episode: JEDI) {
+                ...on Starship {
+                    name
+                }
+            }
+        }
+        "##,
+        "Invalid selection in hero: a fragment on Starship can never apply to a value of type Character",
+    );
+}
+
+#[test]
+fn named_fragments_on_object_honor_type_conditions() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            human(id: "1000") {
+                ...humanFields
+                ...characterFields
+                ...droidFields
+            }
+        }
+
+        fragment humanFields on Human {
+            homePlanet
+        }
+
+        fragment characterFields on Character {
+            name
+        }
+
+        fragment droidFields on Droid {
+            primaryFunction
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Human {
+                respond: Default::default(),
+                id: "1000".to_string(),
+                selection: vec![
+                    star_wars::Human::HomePlanet {
+                        respond: Default::default(),
+                    },
+                    star_wars::Human::Name {
+                        respond: Default::default(),
+                    },
+                ],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn unknown_fragment_coercion() {
+    use self::star_wars;
+    test_coercion_error::<star_wars::Operation>(
+        r##"
+        query {
+            hero(episode: JEDI) {
+                ...nonexistentFields
+            }
+        }
+        "##,
+        "Invalid selection in hero: unknown fragment nonexistentFields",
+    );
+}
+
+#[test]
+fn skip_and_include_directives() {
+    test_coercion::<Operation>(
+        r##"
+    query {
+        lastName @skip(if: true)
+        greeting @include(if: true)
+        sayHello(name: "Emilio") @include(if: false)
+        ... @skip(if: false) {
+            double(num: 2)
+        }
+        ...userFields @include(if: false)
+    }
+
+    fragment userFields on User {
+        lastName
+    }
+    "##,
+        Ok(vec![Operation::Query {
+            selection: vec![
+                User::Greeting {
+                    respond: Default::default(),
+                },
+                User::Double {
+                    respond: Default::default(),
+                    num: 2,
+                },
+            ],
+        }]),
+    );
+}
+
+#[test]
+fn skip_and_include_directives_with_variables() {
+    let variables = if let serde_json::Value::Object(map) =
+        json!({ "withName": false, "withGreeting": true })
+    {
+        map
+    } else {
+        panic!()
+    };
+    let context = tokio_gql::query_validation::ValidationContext::new(variables);
+    test_coercion_with_context::<Operation>(
+        context,
+        r##"
+    query Greet($withName: Boolean!, $withGreeting: Boolean!) {
+        lastName @include(if: $withName)
+        greeting @include(if: $withGreeting) @skip(if: $withName)
+    }
+    "##,
+        Ok(vec![Operation::Query {
+            selection: vec![User::Greeting {
+                respond: Default::default(),
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn operation_selection_by_name() {
+    use self::star_wars;
+    let context = tokio_gql::query_validation::ValidationContext::new(serde_json::Map::new())
+        .with_operation_name(Some("Ships".to_string()));
+    test_coercion_with_context::<star_wars::Operation>(
+        context,
+        r##"
+        query Heroes {
+            hero(episode: JEDI) {
+                name
+            }
+        }
+
+        query Ships {
+            starship(id: "42") {
+                name
+            }
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![star_wars::Query::Starship {
+                respond: Default::default(),
+                id: "42".to_string(),
+                selection: vec![star_wars::Starship::Name {
+                    respond: Default::default(),
+                }],
+            }],
+        }]),
+    );
+}
+
+#[test]
+fn ambiguous_operation_coercion() {
+    use self::star_wars;
+    test_coercion_error::<star_wars::Operation>(
+        r##"
+        query Heroes {
+            hero(episode: JEDI) {
+                name
+            }
+        }
+
+        query Ships {
+            starship(id: "42") {
+                name
+            }
+        }
+        "##,
+        "Invalid query: The document contains several operations, the operation name must be provided",
+    );
+}
+
+#[test]
+fn typename_coercion() {
+    use self::star_wars;
+    test_coercion::<star_wars::Operation>(
+        r##"
+        query {
+            hero(episode: JEDI) {
+                __typename
+                ...on Human {
+                    kind: __typename
+                }
+            }
+            search(text: "Falcon") {
+                __typename
+            }
+        }
+        "##,
+        Ok(vec![star_wars::Operation::Query {
+            selection: vec![
+                star_wars::Query::Hero {
+                    respond: Default::default(),
+                    episode: Some(star_wars::Episode::Jedi),
+                    selection: vec![
+                        star_wars::Character::__Typename {
                             respond: Default::default(),
                         },
-                        star_wars::Character::OnHuman(vec![
-                            star_wars::Human::Height {
-                                respond: Default::default(),
-                                unit: Some(star_wars::LengthUnit::Meter),
-                            },
-                            star_wars::Human::HomePlanet {
-                                respond: Default::default(),
+                        star_wars::Character::OnHuman(vec![star_wars::Human::__Typename {
+                            respond: tokio_gql::response::TypenameResponder {
+                                alias: Some("kind".to_string()),
                             },
-                        ]),
-                        star_wars::Character::OnDroid(vec![star_wars::Droid::PrimaryFunction {
-                            respond: Default::default(),
                         }]),
                     ],
-                }],
-            },
-            star_wars::Operation::Mutation {
-                selection: Vec::new(),
-            },
-            star_wars::Operation::Subscription {
-                selection: Vec::new(),
-            },
-        ]),
+                },
+                star_wars::Query::Search {
+                    respond: Default::default(),
+                    text: Some("Falcon".to_string()),
+                    selection: vec![star_wars::SearchResult::__Typename {
+                        respond: Default::default(),
+                    }],
+                },
+            ],
+        }]),
+    );
+}
+
+#[test]
+fn field_on_union_coercion() {
+    use self::star_wars;
+    test_coercion_error::<star_wars::Operation>(
+        r##"
+        query {
+            search(text: "Falcon") {
+                name
+            }
+        }
+        "##,
+        "Invalid selection in search: only __typename can be selected on union SearchResult, found name",
     );
 }
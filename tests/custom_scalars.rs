@@ -0,0 +1,343 @@
+extern crate futures;
+#[macro_use]
+extern crate tokio_gql;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+extern crate graphql_parser;
+use graphql_parser::query::*;
+
+use futures::future;
+use futures::prelude::*;
+use serde_json as json;
+use std::default::Default;
+use std::sync::Arc;
+use tokio_gql::coercion::*;
+use tokio_gql::execution::Request;
+use tokio_gql::query_validation::ValidationContext;
+use tokio_gql::resolver::{Resolver, ResolverFuture};
+use tokio_gql::response::Response;
+
+mod timestamps {
+    use graphql_parser::query::Value;
+    use serde_json as json;
+    use tokio_gql::coercion::{CoercionError, ScalarValue};
+
+    /// Seconds since the epoch, written as an integer or a string of digits.
+    #[derive(Debug, PartialEq)]
+    pub struct Timestamp(pub i64);
+
+    impl ScalarValue for Timestamp {
+        fn from_query_value(value: &Value) -> Result<Timestamp, CoercionError> {
+            match value {
+                Value::Int(n) => Ok(Timestamp(n.as_i64().unwrap())),
+                Value::String(s) => s
+                    .parse()
+                    .map(Timestamp)
+                    .map_err(|_| CoercionError::invalid_value("DateTime", value)),
+                _ => Err(CoercionError::invalid_value("DateTime", value)),
+            }
+        }
+
+        fn from_json(value: &json::Value) -> Result<Timestamp, CoercionError> {
+            let timestamp = match value {
+                json::Value::Number(n) => n.as_i64(),
+                json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            };
+            timestamp
+                .map(Timestamp)
+                .ok_or_else(|| CoercionError::new(format!("expected DateTime, found {}", value)))
+        }
+
+        fn to_json(&self) -> json::Value {
+            json!(self.0)
+        }
+    }
+}
+
+use timestamps::Timestamp;
+
+/// The `Url` scalar is not mapped, so it is represented by the type of the same name.
+#[derive(Debug, PartialEq)]
+pub struct Url(String);
+
+impl ScalarValue for Url {
+    fn from_query_value(value: &Value) -> Result<Url, CoercionError> {
+        match value {
+            Value::String(s) => Ok(Url(s.to_string())),
+            _ => Err(CoercionError::invalid_value("Url", value)),
+        }
+    }
+
+    fn from_json(value: &json::Value) -> Result<Url, CoercionError> {
+        value
+            .as_str()
+            .map(|s| Url(s.to_string()))
+            .ok_or_else(|| CoercionError::new(format!("expected Url, found {}", value)))
+    }
+
+    fn to_json(&self) -> json::Value {
+        json!(self.0)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(SomethingCompletelyDifferent)]
+#[SomethingCompletelyDifferent(
+    path = "tests/custom_scalars_schema.graphql",
+    scalar(DateTime = "timestamps::Timestamp")
+)]
+struct CalendarSchema;
+
+fn coerce(variables: json::Value, query: &str) -> Result<Vec<Operation>, CoercionError> {
+    let variables = if let json::Value::Object(map) = variables {
+        map
+    } else {
+        panic!("variables should be an object")
+    };
+    let context = ValidationContext::new(variables);
+    let query = parse_query(query).unwrap();
+    Operation::coerce(&query, &context)
+}
+
+#[test]
+fn custom_scalar_arguments() {
+    let expected = Ok(vec![Operation::Query {
+        selection: vec![
+            Calendar::Event {
+                respond: Default::default(),
+                selection: vec![Event::StartsAt {
+                    respond: Default::default(),
+                }],
+                at: Timestamp(1500000000),
+            },
+            Calendar::EventsBetween {
+                respond: Default::default(),
+                after: Some(Timestamp(1400000000)),
+                before: None,
+            },
+            Calendar::BusyAt {
+                respond: Default::default(),
+                times: vec![Timestamp(1), Timestamp(2)],
+            },
+        ],
+    }]);
+
+    assert_eq!(
+        coerce(
+            json!({}),
+            r##"
+            query {
+                event(at: 1500000000) { startsAt }
+                eventsBetween(after: "1400000000", before: null)
+                busyAt(times: [1, "2"])
+            }
+            "##
+        ),
+        expected
+    );
+}
+
+#[test]
+fn invalid_custom_scalar_argument() {
+    assert_eq!(
+        coerce(json!({}), r##"query { event(at: true) { title } }"##)
+            .unwrap_err()
+            .to_string(),
+        "Invalid value for argument at of event: expected DateTime, found true"
+    );
+}
+
+#[test]
+fn custom_scalar_variables() {
+    let expected = Ok(vec![Operation::Query {
+        selection: vec![
+            Calendar::Event {
+                respond: Default::default(),
+                selection: vec![Event::Title {
+                    respond: Default::default(),
+                }],
+                at: Timestamp(1500000000),
+            },
+            Calendar::BusyAt {
+                respond: Default::default(),
+                times: vec![Timestamp(3), Timestamp(4)],
+            },
+        ],
+    }]);
+
+    assert_eq!(
+        coerce(
+            json!({ "at": "1500000000", "times": [3, 4] }),
+            r##"
+            query Events($at: DateTime!, $times: [DateTime!]!) {
+                event(at: $at) { title }
+                busyAt(times: $times)
+            }
+            "##
+        ),
+        expected
+    );
+}
+
+#[test]
+fn custom_scalar_input_object_fields() {
+    let expected = |ends_at| {
+        Ok(vec![Operation::Query {
+            selection: vec![Calendar::CreateEvent {
+                respond: Default::default(),
+                selection: vec![Event::Title {
+                    respond: Default::default(),
+                }],
+                event: EventInput {
+                    title: "Release party".to_string(),
+                    startsAt: Timestamp(10),
+                    endsAt: ends_at,
+                },
+            }],
+        }])
+    };
+
+    assert_eq!(
+        coerce(
+            json!({}),
+            r##"
+            query {
+                createEvent(event: { title: "Release party", startsAt: 10, endsAt: "20" }) { title }
+            }
+            "##
+        ),
+        expected(Some(Timestamp(20)))
+    );
+
+    assert_eq!(
+        coerce(
+            json!({ "event": { "title": "Release party", "startsAt": "10" } }),
+            r##"
+            query CreateEvent($event: EventInput!) {
+                createEvent(event: $event) { title }
+            }
+            "##
+        ),
+        expected(None)
+    );
+}
+
+#[test]
+fn invalid_custom_scalar_input_object_field() {
+    assert_eq!(
+        coerce(
+            json!({ "event": { "title": "Release party", "startsAt": true } }),
+            r##"
+            query CreateEvent($event: EventInput!) {
+                createEvent(event: $event) { title }
+            }
+            "##
+        )
+        .unwrap_err()
+        .to_string(),
+        "Invalid value for argument event of createEvent: expected DateTime, found true"
+    );
+}
+
+struct CalendarResolver;
+
+impl Resolver for CalendarResolver {
+    type Schema = Operation;
+    type Responder = ();
+
+    fn resolve(&self, request: Operation, _responder: ()) -> ResolverFuture {
+        let Operation::Query { selection } = request;
+        let mut response = json::Map::new();
+
+        for field in selection {
+            match field {
+                Calendar::Event { at, .. } => {
+                    response.insert(
+                        "event".to_string(),
+                        json!({ "title": "Launch", "startsAt": at.0 }),
+                    );
+                }
+                Calendar::BusyAt { times, .. } => {
+                    response.insert(
+                        "busyAt".to_string(),
+                        json!(times.contains(&Timestamp(1500000000))),
+                    );
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        Box::new(future::ok(json::Value::Object(response).into()))
+    }
+}
+
+fn execute(request: json::Value) -> json::Value {
+    let schema = Arc::new(
+        graphql_parser::parse_schema(include_str!("custom_scalars_schema.graphql")).unwrap(),
+    );
+    let request: Request = json::from_value(request).unwrap();
+    let result = tokio_gql::execute(&schema, request, &CalendarResolver)
+        .wait()
+        .unwrap();
+    json::to_value(&result).unwrap()
+}
+
+#[test]
+fn custom_scalars_are_executed() {
+    let query = r##"
+    query Events($times: [DateTime!]!) {
+        event(at: "1500000000") { title startsAt }
+        busyAt(times: $times)
+    }
+    "##;
+
+    assert_eq!(
+        execute(json!({
+            "query": query,
+            "variables": { "times": [1400000000, "1500000000"] },
+        })),
+        json!({
+            "data": {
+                "event": { "title": "Launch", "startsAt": 1500000000 },
+                "busyAt": true,
+            },
+        })
+    );
+
+    let response = execute(json!({
+        "query": query,
+        "variables": { "times": [true] },
+    }));
+    assert_eq!(response.get("data"), None);
+    assert_eq!(
+        response["errors"][0]["message"],
+        json!("Invalid value for argument times of busyAt: expected DateTime, found true")
+    );
+}
+
+#[test]
+fn custom_scalar_responders() {
+    match EventStartsAtResponder::default().with(Timestamp(1500000000)) {
+        Response::Immediate(result) => {
+            assert_eq!(result, ("startsAt".to_string(), json!(1500000000)))
+        }
+        Response::Async(_) => unreachable!(),
+    }
+
+    match EventLinkResponder::default().with(Some(Url("https://example.com".to_string()))) {
+        Response::Immediate(result) => {
+            assert_eq!(result, ("link".to_string(), json!("https://example.com")))
+        }
+        Response::Async(_) => unreachable!(),
+    }
+
+    match EventLinkResponder::default().with(None) {
+        Response::Immediate(result) => assert_eq!(result, ("link".to_string(), json!(null))),
+        Response::Async(_) => unreachable!(),
+    }
+}
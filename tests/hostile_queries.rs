@@ -0,0 +1,350 @@
+//! Regression suite of malformed and hostile queries. Validation and coercion must reject them
+//! with an error, never with a panic.
+
+extern crate futures;
+#[macro_use]
+extern crate tokio_gql;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+extern crate graphql_parser;
+
+use std::panic;
+use tokio_gql::coercion::CoerceQueryDocument;
+use tokio_gql::query_validation::{validate_query, ValidationContext};
+
+mod star_wars {
+    #[allow(dead_code)]
+    #[derive(SomethingCompletelyDifferent)]
+    #[SomethingCompletelyDifferent(path = "tests/star_wars_schema.graphql")]
+    struct ComplexSchema;
+}
+
+const SCHEMA: &str = include_str!("star_wars_schema.graphql");
+
+/// Argument literals of every kind, including ones no argument of the schema accepts.
+const LITERALS: &[&str] = &[
+    "0",
+    "-1",
+    "2147483647",
+    "2147483648",
+    "-2147483649",
+    "9223372036854775807",
+    "1.5",
+    "-0.0",
+    "1e300",
+    "\"\"",
+    "\"JEDI\"",
+    "\"\\u0000\"",
+    "true",
+    "false",
+    "null",
+    "JEDI",
+    "FOOT",
+    "NOT_A_VARIANT",
+    "[]",
+    "[null]",
+    "[1, \"two\", THREE, [4]]",
+    "[[[[[]]]]]",
+    "{}",
+    "{stars: 5}",
+    "{stars: null}",
+    "{stars: 2147483648}",
+    "{stars: \"five\", commentary: 3}",
+    "{stars: 5, favorite_color: {red: 1}}",
+    "{stars: 5, favorite_color: {red: 1, green: 2, blue: JEDI}}",
+    "{stars: 5, unknown: {deeply: [{nested: true}]}}",
+    "$var",
+    "$undefined",
+];
+
+/// Values bound to `$var` when a literal refers to it.
+fn variable_values() -> Vec<serde_json::Value> {
+    vec![
+        json!(null),
+        json!(0),
+        json!(2147483648i64),
+        json!(-9223372036854775808i64),
+        json!(18446744073709551615u64),
+        json!(1.5),
+        json!(""),
+        json!("JEDI"),
+        json!(true),
+        json!([]),
+        json!([null, 1, "two"]),
+        json!({}),
+        json!({ "stars": 5 }),
+        json!({ "stars": "five" }),
+        json!({ "stars": 5, "favorite_color": { "red": "1" } }),
+        json!({ "JEDI": null }),
+    ]
+}
+
+/// Queries with a `{}` hole for an argument literal, covering every argument of the schema, with
+/// the literals the argument accepts. `$var` is only declared when it is used.
+const TEMPLATES: &[(&str, &[&str])] = &[
+    (
+        "query($var: Episode) { hero(episode: {}) { name } }",
+        &["null", "JEDI"],
+    ),
+    (
+        "query($var: Episode!) { reviews(episode: {}) { stars commentary } }",
+        &["JEDI"],
+    ),
+    (
+        "query($var: String) { search(text: {}) { __typename } }",
+        &["\"\"", "\"JEDI\"", "\"\\u0000\"", "null"],
+    ),
+    ("query($var: ID!) { character(id: {}) { id name } }", IDS),
+    ("query($var: ID!) { droid(id: {}) { primaryFunction } }", IDS),
+    (
+        "query($var: LengthUnit) { human(id: \"1000\") { height(unit: {}) } }",
+        &["null", "FOOT"],
+    ),
+    (
+        "query($var: LengthUnit) { starship(id: \"1\") { length(unit: {}) coordinates } }",
+        &["null", "FOOT"],
+    ),
+    (
+        "query($var: Int) { hero { friendsConnection(first: {}) { totalCount } } }",
+        &["0", "-1", "2147483647", "null"],
+    ),
+    (
+        "query($var: ID) { hero { friendsConnection(first: 1, after: {}) { edges { cursor } } } }",
+        NULLABLE_IDS,
+    ),
+    (
+        "query($var: Episode) { hero(episode: {}) { ... on Human { height(unit: {}) } } }",
+        &["null"],
+    ),
+    (
+        "mutation($var: ReviewInput!) { createReview(review: {}) { stars } }",
+        &["{stars: 5}"],
+    ),
+    (
+        "mutation($var: Episode) { createReview(episode: {}, review: {stars: 1}) { stars } }",
+        &["null", "JEDI"],
+    ),
+    (
+        "mutation($var: Int!) { createReview(review: {stars: {}}) { stars } }",
+        &["0", "-1", "2147483647"],
+    ),
+    (
+        "mutation($var: String) { createReview(review: {stars: 1, commentary: {}}) { stars } }",
+        &["\"\"", "\"JEDI\"", "\"\\u0000\"", "null"],
+    ),
+    (
+        "mutation($var: ColorInput) { createReview(review: {stars: 1, favorite_color: {}}) { stars } }",
+        &["null"],
+    ),
+    (
+        "mutation($var: Int!) { createReview(review: {stars: 1, favorite_color: {red: {}, green: 0, blue: 0}}) { stars } }",
+        &["0", "-1", "2147483647"],
+    ),
+    (
+        "query($var: Boolean!) { hero { name @skip(if: {}) } }",
+        &["true", "false"],
+    ),
+    (
+        "query($var: Boolean!) { hero { ... on Droid @include(if: {}) { name } } }",
+        &["true", "false"],
+    ),
+    (
+        "query($var: Episode = {}) { hero(episode: $var) { name } }",
+        &["null", "JEDI"],
+    ),
+];
+
+/// IDs are written as strings or integers of any size.
+const IDS: &[&str] = &[
+    "0",
+    "-1",
+    "2147483647",
+    "2147483648",
+    "-2147483649",
+    "9223372036854775807",
+    "\"\"",
+    "\"JEDI\"",
+    "\"\\u0000\"",
+];
+
+const NULLABLE_IDS: &[&str] = &[
+    "0",
+    "-1",
+    "2147483647",
+    "2147483648",
+    "-2147483649",
+    "9223372036854775807",
+    "\"\"",
+    "\"JEDI\"",
+    "\"\\u0000\"",
+    "null",
+];
+
+/// Queries that are malformed in their structure rather than in their arguments.
+const STRUCTURAL: &[&str] = &[
+    "query { hero }",
+    "query { hero { name { first } } }",
+    "query { hero { nonExistent } }",
+    "query { nonExistent }",
+    "query { search(text: \"\") { name } }",
+    "query { search(text: \"\") { ... on Review { stars } } }",
+    "query { search(text: \"\") { ... on Starship { name ... on Human { name } } } }",
+    "query { hero { ... on Starship { length } } }",
+    "query { hero { ... on NotAType { name } } }",
+    "query { hero { ...missing } }",
+    "query { hero { ...starshipFields } } fragment starshipFields on Starship { name }",
+    "query { hero { ...fields } } fragment fields on NotAType { name }",
+    "query { hero { name @skip } }",
+    "query { hero { name @skip(if: true) @include(if: false) @deprecated } }",
+    "query { hero { name @include(unless: true) } }",
+    "query { a: hero { name } a: droid(id: 1) { name } }",
+    "query { hero(episode: JEDI, episode: EMPIRE) { name } }",
+    "query { hero(nonExistent: 1) { name } }",
+    "query { droid { name } }",
+    "query { droid(id: null) { name } }",
+    "query { __type(name: 3) { name } }",
+    "query A { hero { name } } query A { droid(id: 1) { name } }",
+    "query A { hero { name } } mutation B { createReview(review: {stars: 1}) { stars } }",
+    "mutation { hero { name } }",
+    "fragment lonely on Human { name }",
+    "query { hero { ...loop } } fragment loop on Character { ...loop }",
+    "query { hero { ...a } } fragment a on Character { friends { ...b } } fragment b on Character { ... on Human { ...a } }",
+    "query { hero { ...a } } fragment a on Character { name } fragment a on Character { ...a }",
+    "query { hero { name { ...s } } } fragment s on String { length }",
+    "query($var: NotAType) { hero(episode: $var) { name } }",
+    "query($var: [[Episode!]!]!) { hero(episode: $var) { name } }",
+    "query($var: ReviewInput) { hero(episode: $var) { name } }",
+];
+
+/// Queries that look hostile but are valid.
+const UNUSUAL: &[&str] = &[
+    "query { hero { friends { friends { friends { friends { friends { name } } } } } } }",
+    "query { hero { ... { name } } }",
+    "query { __typename __schema { types { name } } }",
+    "subscription { reviewAdded { stars } }",
+];
+
+/// Validates and coerces `query`, and coerces it without validation as well, so coercion is
+/// exercised on documents validation would have rejected. Returns whether parsing, validation or
+/// coercion rejected the query.
+fn check(query: &str, variables: &serde_json::Map<String, serde_json::Value>) -> bool {
+    let schema = graphql_parser::parse_schema(SCHEMA).unwrap();
+    let document = match graphql_parser::parse_query(query) {
+        Ok(document) => document,
+        Err(_) => return true,
+    };
+
+    let rejected = match validate_query(&document, variables.clone(), None, &schema) {
+        Ok(context) => star_wars::Operation::coerce(&document, &context).is_err(),
+        Err(_) => true,
+    };
+
+    let context = ValidationContext::new(variables.clone());
+    let _ = star_wars::Operation::coerce(&document, &context);
+
+    rejected
+}
+
+fn assert_no_panics(cases: Vec<(String, serde_json::Map<String, serde_json::Value>)>) {
+    let panicking: Vec<_> = cases
+        .iter()
+        .filter(|(query, variables)| panic::catch_unwind(|| check(query, variables)).is_err())
+        .collect();
+
+    assert!(
+        panicking.is_empty(),
+        "{} of {} queries panicked:\n{:#?}",
+        panicking.len(),
+        cases.len(),
+        panicking
+    );
+}
+
+fn variables_with(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    let mut variables = serde_json::Map::new();
+    variables.insert("var".to_string(), value);
+    variables
+}
+
+/// Removes the declaration of `$var` from `query` when it is not used, so that the literal is the
+/// only thing that can make the query invalid.
+fn without_unused_variable(query: String) -> String {
+    if query.matches("$var").count() > 1 {
+        return query;
+    }
+
+    let start = query.find("($var").unwrap();
+    let end = start + query[start..].find(')').unwrap();
+    format!("{}{}", &query[..start], &query[end + 1..])
+}
+
+#[test]
+fn hostile_argument_literals() {
+    let mut cases = Vec::new();
+    let mut invalid = Vec::new();
+
+    for (template, accepted) in TEMPLATES {
+        for literal in LITERALS {
+            let query = template.replace("{}", literal);
+
+            if literal.starts_with("$var") {
+                for value in variable_values() {
+                    cases.push((query.clone(), variables_with(value)));
+                }
+            } else {
+                let query = without_unused_variable(query);
+                if !accepted.contains(literal) {
+                    invalid.push(query.clone());
+                }
+                cases.push((query, serde_json::Map::new()));
+            }
+        }
+    }
+
+    assert_no_panics(cases);
+
+    let accepted: Vec<_> = invalid
+        .iter()
+        .filter(|query| !check(query, &serde_json::Map::new()))
+        .collect();
+    assert!(
+        accepted.is_empty(),
+        "queries were accepted:\n{:#?}",
+        accepted
+    );
+}
+
+#[test]
+fn hostile_query_structure() {
+    let cases = STRUCTURAL
+        .iter()
+        .chain(UNUSUAL.iter())
+        .map(|query| (query.to_string(), serde_json::Map::new()))
+        .collect();
+
+    assert_no_panics(cases);
+
+    let accepted: Vec<_> = STRUCTURAL
+        .iter()
+        .filter(|query| !check(query, &serde_json::Map::new()))
+        .collect();
+    assert!(
+        accepted.is_empty(),
+        "queries were accepted:\n{:#?}",
+        accepted
+    );
+
+    let rejected: Vec<_> = UNUSUAL
+        .iter()
+        .filter(|query| check(query, &serde_json::Map::new()))
+        .collect();
+    assert!(
+        rejected.is_empty(),
+        "queries were rejected:\n{:#?}",
+        rejected
+    );
+}
@@ -9,7 +9,7 @@ extern crate serde_json;
 use futures::prelude::*;
 use serde_json as json;
 use tokio_gql::resolver::*;
-use tokio_gql::response::Response;
+use tokio_gql::response::{Resolved, Response};
 
 mod star_wars {
     #[allow(dead_code)]
@@ -42,7 +42,34 @@ fn basic_sync_field() {
             Response::Async(_) => unreachable!(),
             Response::Immediate(result) => assert_eq!(
                 result,
-                ("homePlanet", ::serde_json::Value::String("Vosges".into()))
+                (
+                    "homePlanet".to_string(),
+                    ::serde_json::Value::String("Vosges".into())
+                )
+            ),
+        }
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn aliased_sync_field() {
+    let field = star_wars::Human::HomePlanet {
+        respond: star_wars::HumanHomePlanetResponder {
+            alias: Some("birthplace".to_string()),
+        },
+    };
+
+    if let star_wars::Human::HomePlanet { respond } = field {
+        match respond.with(Some("Vosges".into())) {
+            Response::Async(_) => unreachable!(),
+            Response::Immediate(result) => assert_eq!(
+                result,
+                (
+                    "birthplace".to_string(),
+                    ::serde_json::Value::String("Vosges".into())
+                )
             ),
         }
     } else {
@@ -63,44 +90,48 @@ fn basic_async_object() {
                     respond: Default::default(),
                 },
                 star_wars::PageInfo::EndCursor {
+                    respond: star_wars::PageInfoEndCursorResponder {
+                        alias: Some("lastCursor".to_string()),
+                    },
+                },
+                star_wars::PageInfo::__Typename {
                     respond: Default::default(),
                 },
             ],
         }],
     };
 
+    fn load_page_info(
+        _selection: &[star_wars::PageInfo],
+    ) -> impl Future<Item = (&'static str, &'static str, bool), Error = tokio_gql::errors::ResolverError>
+    {
+        ::futures::future::ok(("to_alpha", "to_omega", true))
+    }
+
     fn resolve_page_info(
-        selection: &[star_wars::PageInfo],
-    ) -> impl Future<
-        Item = impl Fn(star_wars::PageInfo) -> Response,
-        Error = tokio_gql::errors::ResolverError,
-    > {
-        ::futures::future::ok(("to_alpha", "to_omega", true)).map(|(start, end, has_next_page)| {
-            move |field| {
-                unimplemented!();
-                match field {
-                    star_wars::PageInfo::StartCursor { respond } => {
-                        respond.with(Some(start.to_string().into()))
-                    }
-                    star_wars::PageInfo::EndCursor { respond } => {
-                        respond.with(Some(end.to_string()))
-                    }
-                    star_wars::PageInfo::HasNextPage { respond } => respond.with(has_next_page),
-                }
-            }
-        })
+        field: star_wars::PageInfo,
+        data: &(&'static str, &'static str, bool),
+    ) -> Response {
+        let (start, end, has_next_page) = *data;
+
+        match field {
+            star_wars::PageInfo::StartCursor { respond } => respond.with(Some(start.to_string())),
+            star_wars::PageInfo::EndCursor { respond } => respond.with(Some(end.to_string())),
+            star_wars::PageInfo::HasNextPage { respond } => respond.with(has_next_page),
+            star_wars::PageInfo::__Typename { .. } => unreachable!("answered by the responder"),
+        }
     }
 
     fn load_friends_connection(
-        selection: &[star_wars::FriendsConnection],
+        _selection: &[star_wars::FriendsConnection],
     ) -> impl Future<Item = (), Error = tokio_gql::errors::ResolverError> {
         ::futures::future::ok(())
     }
 
-    fn resolve_friends_connection(data: &(), field: star_wars::FriendsConnection) -> Response {
+    fn resolve_friends_connection(field: star_wars::FriendsConnection, _data: &()) -> Response {
         match field {
             star_wars::FriendsConnection::PageInfo { selection, respond } => {
-                respond.to(selection, resolve_page_info)
+                respond.to(selection, load_page_info, resolve_page_info)
             }
             _ => unimplemented!(),
         }
@@ -110,13 +141,187 @@ fn basic_async_object() {
         respond, selection, ..
     } = field
     {
-        let fut = { respond.to(selection, resolve_friends_connection) };
+        match respond.to(selection, load_friends_connection, resolve_friends_connection) {
+            Response::Async(fut) => assert_eq!(
+                fut.wait().unwrap(),
+                (
+                    "friendsConnection".to_string(),
+                    Resolved::from(json!({
+                        "pageInfo": {
+                            "hasNextPage": true,
+                            "lastCursor": "to_omega",
+                            "__typename": "PageInfo",
+                        }
+                    }))
+                )
+            ),
+            Response::Immediate(_) => unreachable!(),
+        }
+    } else {
+        unreachable!();
+    }
+}
 
-        match fut.wait() {
-            Ok(i) => assert_eq!(i, json!({ "hasNextPage": true, "endCursor": "to_omega" })),
-            _ => unreachable!(),
+#[test]
+fn interface_with_typename_and_fragments() {
+    let field = star_wars::Query::Hero {
+        respond: Default::default(),
+        episode: None,
+        selection: vec![
+            star_wars::Character::Name {
+                respond: Default::default(),
+            },
+            star_wars::Character::__Typename {
+                respond: tokio_gql::response::TypenameResponder {
+                    alias: Some("kind".to_string()),
+                },
+            },
+            star_wars::Character::OnHuman(vec![star_wars::Human::HomePlanet {
+                respond: Default::default(),
+            }]),
+            star_wars::Character::OnDroid(vec![star_wars::Droid::PrimaryFunction {
+                respond: Default::default(),
+            }]),
+        ],
+    };
+
+    fn load_human(
+        _selection: &[star_wars::Human],
+    ) -> impl Future<Item = &'static str, Error = tokio_gql::errors::ResolverError> {
+        ::futures::future::ok("Tatooine")
+    }
+
+    fn resolve_human(field: star_wars::Human, home_planet: &&'static str) -> Response {
+        match field {
+            star_wars::Human::HomePlanet { respond } => respond.with(Some(home_planet.to_string())),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn resolve_character(field: star_wars::Character) -> Response {
+        match field {
+            star_wars::Character::Name { respond } => respond.with("Luke Skywalker".to_string()),
+            star_wars::Character::OnHuman(selection) => Response::Async(Box::new(
+                star_wars::HumanResponder::default()
+                    .to(selection, load_human, resolve_human)
+                    .map(|fields| (String::new(), fields)),
+            )),
+            star_wars::Character::OnDroid(_) => unreachable!("the hero is a human"),
+            _ => unimplemented!(),
+        }
+    }
+
+    if let star_wars::Query::Hero {
+        respond, selection, ..
+    } = field
+    {
+        match respond.to("Human", selection, resolve_character) {
+            Response::Async(fut) => assert_eq!(
+                fut.wait().unwrap(),
+                (
+                    "hero".to_string(),
+                    Resolved::from(json!({
+                        "name": "Luke Skywalker",
+                        "kind": "Human",
+                        "homePlanet": "Tatooine",
+                    }))
+                )
+            ),
+            Response::Immediate(_) => unreachable!(),
         }
     } else {
         unreachable!();
     }
 }
+
+fn resolve_connection_with_failing_field(failing_field: &'static str) -> (String, Resolved) {
+    use tokio_gql::errors::ResolverError;
+
+    fn failure(field: &'static str) -> Response {
+        Response::Async(Box::new(::futures::future::err(ResolverError::new(
+            format!("Could not resolve {}", field),
+        ))))
+    }
+
+    let selection = vec![
+        star_wars::FriendsConnection::TotalCount {
+            respond: Default::default(),
+        },
+        star_wars::FriendsConnection::PageInfo {
+            respond: Default::default(),
+            selection: vec![star_wars::PageInfo::HasNextPage {
+                respond: Default::default(),
+            }],
+        },
+    ];
+
+    let resolve_page_info = move |field: star_wars::PageInfo, _data: &()| match field {
+        star_wars::PageInfo::HasNextPage { .. } if failing_field == "hasNextPage" => {
+            failure(failing_field)
+        }
+        star_wars::PageInfo::HasNextPage { respond } => respond.with(true),
+        _ => unimplemented!(),
+    };
+
+    let resolve_friends_connection =
+        move |field: star_wars::FriendsConnection, _data: &()| match field {
+            star_wars::FriendsConnection::TotalCount { .. } if failing_field == "totalCount" => {
+                failure(failing_field)
+            }
+            star_wars::FriendsConnection::TotalCount { respond } => respond.with(Some(3)),
+            star_wars::FriendsConnection::PageInfo { selection, respond } => respond.to(
+                selection,
+                |_: &[star_wars::PageInfo]| ::futures::future::ok(()),
+                resolve_page_info,
+            ),
+            _ => unimplemented!(),
+        };
+
+    match star_wars::HumanFriendsConnectionResponder::default().to(
+        selection,
+        |_: &[star_wars::FriendsConnection]| ::futures::future::ok(()),
+        resolve_friends_connection,
+    ) {
+        Response::Async(fut) => fut.wait().unwrap(),
+        Response::Immediate(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn failing_nullable_fields_are_null() {
+    use tokio_gql::errors::ResolverError;
+
+    assert_eq!(
+        resolve_connection_with_failing_field("totalCount"),
+        (
+            "friendsConnection".to_string(),
+            Resolved {
+                value: json!({ "totalCount": null, "pageInfo": { "hasNextPage": true } }),
+                errors: vec![
+                    ResolverError::new("Could not resolve totalCount")
+                        .with_path(vec!["totalCount".into()]),
+                ],
+            }
+        )
+    );
+}
+
+#[test]
+fn failing_non_null_fields_null_their_parent() {
+    use tokio_gql::errors::ResolverError;
+
+    // `hasNextPage` and `pageInfo` are both non-null, so the null bubbles up to the connection.
+    assert_eq!(
+        resolve_connection_with_failing_field("hasNextPage"),
+        (
+            "friendsConnection".to_string(),
+            Resolved {
+                value: json::Value::Null,
+                errors: vec![
+                    ResolverError::new("Could not resolve hasNextPage")
+                        .with_path(vec!["pageInfo".into(), "hasNextPage".into()]),
+                ],
+            }
+        )
+    );
+}